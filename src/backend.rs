@@ -0,0 +1,41 @@
+use futures::future::BoxFuture;
+
+use crate::error::BoxError;
+
+pub type HttpRequest = http::Request<Vec<u8>>;
+
+pub type HttpResponse = http::Response<Vec<u8>>;
+
+/// HTTP backend used to talk to the OAuth 2.0 endpoints.
+pub trait HttpClient: Send + Sync + 'static {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, BoxError>>;
+}
+
+impl HttpClient for reqwest::Client {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, BoxError>> {
+        Box::pin(async move {
+            let request = reqwest::Request::try_from(request)?;
+            let response = reqwest::Client::execute(self, request).await?;
+            let mut builder = http::Response::builder()
+                .status(response.status())
+                .version(response.version());
+            if let Some(headers) = builder.headers_mut() {
+                headers.clone_from(response.headers());
+            }
+            let body = response.bytes().await?;
+            Ok(builder.body(body.to_vec())?)
+        })
+    }
+}
+
+/// `POST` request with an `application/x-www-form-urlencoded` body, accepting JSON
+pub(crate) fn form_request(uri: &str, body: String) -> Result<HttpRequest, http::Error> {
+    http::Request::post(uri)
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .header(http::header::ACCEPT, "application/json")
+        .header(http::header::CONTENT_LENGTH, body.len())
+        .body(body.into_bytes())
+}
@@ -1,14 +1,13 @@
-use google_oauth::{ClientSecret, UnauthorizedClient};
+use google_oauth::scope::Scope;
+use google_oauth::{ClientConfig, UnauthorizedClient};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let secret_file = tokio::fs::File::open("tmp/client_secret.json").await?;
-    let secret = ClientSecret::read_from_file(secret_file).await?;
-    let client = UnauthorizedClient::builder()
-        .redirect_uri("http://localhost:8080/oauth2/callback")
-        .add_scope(google_oauth::scope::Calendar)
-        .secret(&secret.web)
-        .build()?;
+    let config = ClientConfig::new(
+        "http://localhost:8080/oauth2/callback",
+        google_oauth::scope::Calendar.space_delimited(),
+    );
+    let client = UnauthorizedClient::from_secret_file("tmp/client_secret.json", config).await?;
     println!("{}", client.generate_url());
     Ok(())
 }
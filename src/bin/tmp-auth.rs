@@ -1,18 +1,14 @@
 use std::{future::IntoFuture, net::SocketAddr, sync::Arc};
 
 use anyhow::anyhow;
-use axum::extract::{Query, State};
 use futures::TryFutureExt;
-use serde::{Deserialize, Serialize};
+use rand::distributions::{Alphanumeric, DistString};
 use tokio::sync::{mpsc, Notify};
 use tracing_subscriber::EnvFilter;
 
-use google_oauth::{AuthorizedClient, ClientSecret, UnauthorizedClient};
-
-#[derive(Clone)]
-struct AppState {
-    pub code_tx: mpsc::UnboundedSender<String>,
-}
+use google_oauth::{
+    AuthorizedClient, CallbackState, ClientSecret, FileTokenStore, TokenStore, UnauthorizedClient,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -20,12 +16,16 @@ async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt().with_env_filter(env_filter).init();
 
     let client = unauthorized_client().await?;
-    tracing::info!("authorize url: {}", client.generate_url());
+    let csrf_state = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    tracing::info!(
+        "authorize url: {}",
+        client.generate_url_with_state(&csrf_state)
+    );
 
     let (code_tx, code_rx) = mpsc::unbounded_channel();
-    let state = AppState { code_tx };
+    let state = CallbackState::new(csrf_state, code_tx);
     let layer = tower::ServiceBuilder::new().layer(tower_http::trace::TraceLayer::new_for_http());
-    let router = make_router(state).layer(layer);
+    let router = google_oauth::make_callback_router("/oauth2/callback", state).layer(layer);
     let addr = bind_addr()?;
     tracing::info!("listening on {addr}");
     let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -52,14 +52,6 @@ fn bind_addr() -> anyhow::Result<SocketAddr> {
     Ok(addr)
 }
 
-fn make_router(state: AppState) -> axum::Router {
-    use axum::routing;
-
-    axum::Router::new()
-        .route("/oauth2/callback", routing::get(callback))
-        .with_state(state)
-}
-
 async fn wait_code_with_notify(
     mut code_rx: mpsc::UnboundedReceiver<String>,
     notify: Arc<Notify>,
@@ -87,30 +79,10 @@ async fn unauthorized_client() -> anyhow::Result<UnauthorizedClient> {
     Ok(client)
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-struct CallbackParam {
-    code: String,
-}
-
-#[tracing::instrument(skip_all)]
-async fn callback(
-    State(state): State<AppState>,
-    Query(param): Query<CallbackParam>,
-) -> (http::StatusCode, &'static str) {
-    let CallbackParam { code } = param;
-    tracing::info!("authorized with code: {code}");
-    let Ok(()) = state.code_tx.send(code) else {
-        tracing::error!("mpsc channel error");
-        return (http::StatusCode::INTERNAL_SERVER_ERROR, "channel error");
-    };
-    (http::StatusCode::OK, "authorized")
-}
-
 #[tracing::instrument(skip_all)]
 async fn export_token(client: &AuthorizedClient) -> anyhow::Result<()> {
-    let token = client.token();
-    let token = serde_json::to_string_pretty(token)?;
-    tokio::fs::write("tmp/authorized_token.json", token).await?;
+    let store = FileTokenStore::new("tmp/authorized_token.json");
+    store.store(client.token()).await?;
     tracing::info!("exported token to tmp/authorized_token.json");
     Ok(())
 }
@@ -0,0 +1,131 @@
+//! Synchronous wrappers driving the async clients on a dedicated runtime.
+//! Must not be used from within an async context.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+use crate::client::{AuthorizedClient, Token, UnauthorizedClient};
+use crate::error::Error;
+
+fn runtime() -> Result<Arc<Runtime>, Error> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    Ok(Arc::new(runtime))
+}
+
+#[derive(Clone)]
+pub struct BlockingUnauthorizedClient {
+    inner: UnauthorizedClient,
+    runtime: Arc<Runtime>,
+}
+
+impl BlockingUnauthorizedClient {
+    pub fn new(inner: UnauthorizedClient) -> Result<Self, Error> {
+        let runtime = runtime()?;
+        Ok(Self { inner, runtime })
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &UnauthorizedClient {
+        &self.inner
+    }
+
+    pub fn generate_url(&self) -> String {
+        self.inner.generate_url()
+    }
+
+    pub fn acquire_token_with<'a, S>(&'a self, code: S) -> Result<Token, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.runtime.block_on(self.inner.acquire_token_with(code))
+    }
+
+    pub fn authorize_with_code<'a, S>(&'a self, code: S) -> Result<BlockingAuthorizedClient, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let inner = self
+            .runtime
+            .block_on(self.inner.authorize_with_code(code))?;
+        Ok(BlockingAuthorizedClient {
+            inner,
+            runtime: Arc::clone(&self.runtime),
+        })
+    }
+
+    pub fn authorize_with_token(&self, token: Token) -> BlockingAuthorizedClient {
+        BlockingAuthorizedClient {
+            inner: self.inner.authorize_with_token(token),
+            runtime: Arc::clone(&self.runtime),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BlockingAuthorizedClient {
+    inner: AuthorizedClient,
+    runtime: Arc<Runtime>,
+}
+
+impl BlockingAuthorizedClient {
+    pub fn new(inner: AuthorizedClient) -> Result<Self, Error> {
+        let runtime = runtime()?;
+        Ok(Self { inner, runtime })
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &AuthorizedClient {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> AuthorizedClient {
+        self.inner
+    }
+
+    #[inline]
+    pub fn token(&self) -> &Token {
+        self.inner.token()
+    }
+
+    pub fn refresh(self) -> Result<Self, Error> {
+        let Self { inner, runtime } = self;
+        let inner = runtime.block_on(inner.refresh())?;
+        Ok(Self { inner, runtime })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::Calendar;
+    use crate::test_util::{secret, serve, token_response, unauthorized_builder};
+
+    #[test]
+    fn test_blocking_token_exchange() {
+        let server = tokio::runtime::Runtime::new().unwrap();
+        let router = axum::Router::new().route(
+            "/token",
+            axum::routing::post(|body: String| async move {
+                let mut response = token_response(&Calendar);
+                if body.contains("grant_type=refresh_token") {
+                    response["access_token"] = "refreshed".into();
+                }
+                response["refresh_token"] = "refresh".into();
+                axum::Json(response)
+            }),
+        );
+        let addr = server.block_on(serve(router));
+        let mut secret = secret();
+        secret.token_uri = format!("http://{addr}/token");
+        let client = unauthorized_builder().secret(&secret).build().unwrap();
+        let client = BlockingUnauthorizedClient::new(client).unwrap();
+        let client = client.authorize_with_code("code").unwrap();
+        assert_eq!(client.token().access_token(), "access");
+        let client = client.refresh().unwrap();
+        assert_eq!(client.token().access_token(), "refreshed");
+    }
+}
@@ -1,34 +1,172 @@
 use std::borrow::Cow;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::scope::{self, Scope, SpaceDelimitedScope};
-use crate::secret::WebClientSecret;
+use crate::backend::{form_request, HttpClient, HttpResponse};
+use crate::endpoints::Endpoints;
+use crate::error::{BoxError, Error, TokenError, TransportEndpoint, TransportPhase};
+use crate::scope::{self, DynSingleScope, Scope, SingleScope, SpaceDelimitedScope};
+use crate::secret::{ClientSecret, SecretString, WebClientSecret};
+use crate::store::TokenStore;
 
+mod api;
+mod authorization;
+#[cfg(feature = "calendar")]
 pub mod calendar;
+#[cfg(feature = "drive")]
+pub mod drive;
+mod id_token;
 mod misc;
+mod pagination;
+mod pkce;
+mod retry;
+mod revoke;
+mod service_account;
+mod shared;
+mod token_info;
+mod userinfo;
 
-pub use misc::{AuthorizationCode, Bearer, RefreshToken};
+pub use authorization::AuthorizationRequest;
+pub use id_token::IdTokenClaims;
+pub use misc::{
+    AuthorizationCode, Bearer, JwtBearer, Prompt, PromptError, PromptSet, RefreshToken,
+    ResponseType,
+};
+pub use pagination::{paginate, Page, Paginated};
+pub use pkce::PkceVerifier;
+pub use retry::RetryPolicy;
+pub use revoke::RevokeOnDrop;
+pub use service_account::{Claims as ServiceAccountClaims, ServiceAccountClient};
+pub use shared::SharedAuthorizedClient;
+pub use token_info::TokenInfo;
+pub use userinfo::UserInfo;
 
-#[derive(Debug, Clone)]
+/// Built with [`ClientConfig::new`]; further fields may be added.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct ClientConfig {
     pub redirect_uri: String,
     pub scope: SpaceDelimitedScope,
+    /// Appended to the authorization URL after the standard parameters, in order.
+    /// A `prompt` here is dropped while [`ClientConfig::prompt`] is set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_params: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "ResponseType::is_default")]
+    pub response_type: ResponseType,
+    /// Left out of the authorization URL when empty
+    #[serde(default, skip_serializing_if = "PromptSet::is_empty")]
+    pub prompt: PromptSet,
+}
+
+impl ClientConfig {
+    /// A config requesting a code for `scope`, with no extra parameters or prompt.
+    pub fn new<S: Into<String>>(redirect_uri: S, scope: SpaceDelimitedScope) -> Self {
+        Self {
+            redirect_uri: redirect_uri.into(),
+            scope,
+            extra_params: Vec::new(),
+            response_type: ResponseType::default(),
+            prompt: PromptSet::new(),
+        }
+    }
+
+    /// Reads `{PREFIX_}OAUTH_REDIRECT_URI` and the space-delimited `{PREFIX_}OAUTH_SCOPE`.
+    pub fn from_env(prefix: Option<&str>) -> Result<Self, Error> {
+        let var = |name: &str| {
+            let key = match prefix {
+                Some(prefix) => format!("{prefix}_{name}"),
+                None => name.to_string(),
+            };
+            std::env::var(key).ok().filter(|v| !v.is_empty())
+        };
+        let redirect_uri = var("OAUTH_REDIRECT_URI").ok_or(Error::MissingRedirectUri)?;
+        let scope = var("OAUTH_SCOPE").ok_or(Error::EmptyScope)?;
+        let scope = scope.trim().parse().map_err(Error::InvalidScope)?;
+        Ok(Self::new(redirect_uri, scope))
+    }
+
+    /// Query parameters of the authorization URL following `client_id`,
+    /// in the order [`UnauthorizedClient::generate_url`] emits them. Values are not encoded.
+    /// `access_type=offline` is only requested along with a code.
+    pub fn to_request_params(&self) -> Vec<(&'static str, String)> {
+        let Self {
+            redirect_uri,
+            scope,
+            response_type,
+            prompt,
+            ..
+        } = self;
+        let mut params = vec![
+            ("redirect_uri", redirect_uri.clone()),
+            ("scope", scope.to_string()),
+            ("response_type", response_type.to_string()),
+        ];
+        if response_type.includes_code() {
+            params.push(("access_type", "offline".to_string()));
+        }
+        if !prompt.is_empty() {
+            params.push(("prompt", prompt.to_string()));
+        }
+        params
+    }
 }
 
 #[derive(Clone)]
 pub struct UnauthorizedClient {
     secret: WebClientSecret,
     config: ClientConfig,
-    client: reqwest::Client,
+    endpoints: Endpoints,
+    http: Arc<dyn HttpClient>,
+    require_refresh_token: bool,
 }
 
 impl UnauthorizedClient {
     pub fn new(secret: WebClientSecret, config: ClientConfig) -> Self {
         Self {
+            endpoints: Endpoints::from_secret(&secret),
             secret,
             config,
-            client: Default::default(),
+            http: Arc::new(reqwest::Client::new()),
+            require_refresh_token: false,
+        }
+    }
+
+    pub fn with_endpoints(self, endpoints: Endpoints) -> Self {
+        Self { endpoints, ..self }
+    }
+
+    #[inline]
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    /// Assembles a client from a secret and a config loaded separately, e.g. from files.
+    #[inline]
+    pub fn from_parts(secret: WebClientSecret, config: ClientConfig) -> Self {
+        Self::new(secret, config)
+    }
+
+    /// Reads a `client_secret.json` downloaded from the Google Cloud console at `path`
+    /// and pairs its `web` secret with `config`.
+    #[tracing::instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn from_secret_file<P>(path: P, config: ClientConfig) -> Result<Self, Error>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let file = tokio::fs::File::open(path.as_ref()).await?;
+        let ClientSecret { web } = ClientSecret::read_from_file(file).await?;
+        Ok(Self::new(web, config))
+    }
+
+    /// Replaces the backend used for the token exchange.
+    pub fn with_http_client<H: HttpClient>(self, http: H) -> Self {
+        Self {
+            http: Arc::new(http),
+            ..self
         }
     }
 
@@ -37,79 +175,291 @@ impl UnauthorizedClient {
     }
 
     pub fn generate_url(&self) -> String {
-        use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+        self.generate_url_with_params(&self.config, Vec::new())
+    }
 
-        let Self { secret, config, .. } = self;
-        let WebClientSecret {
-            client_id,
-            auth_uri,
-            ..
-        } = secret;
-        let ClientConfig {
-            redirect_uri,
-            scope,
-        } = config;
-        let client_id = utf8_percent_encode(client_id, NON_ALPHANUMERIC);
-        let redirect_uri = utf8_percent_encode(redirect_uri, NON_ALPHANUMERIC);
-        let scope = scope.to_string();
-        let scope = utf8_percent_encode(&scope, NON_ALPHANUMERIC);
-        let query = [
-            format!("client_id={client_id}"),
-            format!("redirect_uri={redirect_uri}"),
-            format!("scope={scope}"),
-            "response_type=code".to_string(),
-            "access_type=offline".to_string(),
-            // TODO: add state
-        ]
-        .join("&");
-        format!("{auth_uri}?{query}")
+    /// Authorization URL carrying an OpenID Connect `nonce`,
+    /// to be checked with [`Token::id_token_claims`].
+    pub fn generate_url_with_nonce(&self, nonce: &str) -> String {
+        self.generate_url_with_params(&self.config, vec![("nonce", nonce.to_string())])
+    }
+
+    /// Authorization URL carrying an opaque `state`,
+    /// echoed back to the redirect URI and checked by [`crate::make_callback_router`].
+    pub fn generate_url_with_state(&self, state: &str) -> String {
+        self.generate_url_with_params(&self.config, vec![("state", state.to_string())])
+    }
+
+    /// Authorization URL carrying the S256 `code_challenge` of `pkce`,
+    /// to be exchanged with [`Self::acquire_token_with_pkce`].
+    pub fn generate_url_with_pkce(&self, pkce: &PkceVerifier) -> String {
+        self.generate_url_with_params(&self.config, pkce_params(pkce))
+    }
+
+    /// Authorization URL requesting `scope` instead of the configured one,
+    /// e.g. for incremental authorization.
+    pub fn generate_url_with_scope(&self, scope: &dyn Scope) -> String {
+        let config = ClientConfig {
+            scope: scope.space_delimited(),
+            ..self.config.clone()
+        };
+        self.generate_url_with_params(&config, Vec::new())
+    }
+
+    /// Redirect URIs [`Self::generate_url_for`] and [`Self::acquire_token_for`] accept:
+    /// the configured one followed by those registered in the secret.
+    pub fn redirect_uris(&self) -> Vec<&str> {
+        let mut uris = vec![self.config.redirect_uri.as_str()];
+        for uri in &self.secret.redirect_uris {
+            if !uris.contains(&uri.as_str()) {
+                uris.push(uri);
+            }
+        }
+        uris
     }
 
-    pub async fn acquire_token_with<'a, S>(&'a self, code: S) -> reqwest::Result<Token>
+    fn check_redirect_uri(&self, redirect_uri: &str) -> Result<(), Error> {
+        let allowed = self.redirect_uris();
+        if allowed.contains(&redirect_uri) {
+            return Ok(());
+        }
+        Err(Error::UnregisteredRedirectUri {
+            redirect_uri: redirect_uri.to_string(),
+            allowed: allowed.into_iter().map(str::to_string).collect(),
+        })
+    }
+
+    /// Authorization URL redirecting to `redirect_uri`, one of [`Self::redirect_uris`],
+    /// e.g. for an app registered for both a web and a localhost callback.
+    pub fn generate_url_for(&self, redirect_uri: &str) -> Result<String, Error> {
+        self.check_redirect_uri(redirect_uri)?;
+        let config = ClientConfig {
+            redirect_uri: redirect_uri.to_string(),
+            ..self.config.clone()
+        };
+        Ok(self.generate_url_with_params(&config, Vec::new()))
+    }
+
+    fn generate_url_with_params(
+        &self,
+        config: &ClientConfig,
+        extra: Vec<(&'static str, String)>,
+    ) -> String {
+        let client_id = &self.secret.client_id;
+        authorization_url(client_id, &self.endpoints.auth_uri, config, extra)
+    }
+
+    /// Exchanges `code` for a token.
+    /// Fails with [`Error::CodeNotRequested`] under [`ResponseType::Token`],
+    /// where the token arrives in the redirect URI instead.
+    pub async fn acquire_token_with<'a, S>(&'a self, code: S) -> Result<Token, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let redirect_uri = &self.config.redirect_uri;
+        self.acquire_token_with_redirect(code, redirect_uri).await
+    }
+
+    /// [`Self::acquire_token_with`] for a code obtained through
+    /// [`Self::generate_url_with_pkce`], sending the `code_verifier` of `pkce`.
+    pub async fn acquire_token_with_pkce<'a, S>(
+        &'a self,
+        code: S,
+        pkce: &PkceVerifier,
+    ) -> Result<Token, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let redirect_uri = Cow::Borrowed(self.config.redirect_uri.as_str());
+        self.exchange_code(code.into(), redirect_uri, Some(pkce))
+            .await
+    }
+
+    /// [`Self::acquire_token_with`] for a code issued to `redirect_uri`
+    /// instead of the configured one, e.g. when the authorization URL was generated elsewhere.
+    pub async fn acquire_token_with_redirect<'a, S, R>(
+        &'a self,
+        code: S,
+        redirect_uri: R,
+    ) -> Result<Token, Error>
+    where
+        S: Into<Cow<'a, str>>,
+        R: Into<Cow<'a, str>>,
+    {
+        self.exchange_code(code.into(), redirect_uri.into(), None)
+            .await
+    }
+
+    #[tracing::instrument(
+        name = "acquire_token",
+        skip_all,
+        fields(grant_type = %AuthorizationCode::new())
+    )]
+    async fn exchange_code(
+        &self,
+        code: Cow<'_, str>,
+        redirect_uri: Cow<'_, str>,
+        pkce: Option<&PkceVerifier>,
+    ) -> Result<Token, Error> {
+        let response_type = self.config.response_type;
+        if !response_type.includes_code() {
+            return Err(Error::CodeNotRequested(response_type));
+        }
+        let (token_uri, body) = self.token_request(code, redirect_uri, pkce);
+        let request = form_request(&token_uri, body)?;
+        let response = self.http.execute(request).await.map_err(|err| {
+            tracing::error!(outcome = "error", err, "could not send request");
+            Error::transport(TransportEndpoint::Token, TransportPhase::Send, err)
+        })?;
+        received_token(response)
+            .and_then(|token| {
+                if self.require_refresh_token && !token.has_refresh_token() {
+                    return Err(Error::RefreshTokenNotIssued);
+                }
+                Ok(token)
+            })
+            .inspect(|_| tracing::info!(outcome = "ok", "acquired token"))
+            .inspect_err(|err| {
+                let err = err as &dyn std::error::Error;
+                tracing::error!(outcome = "error", err, "could not acquire token");
+            })
+    }
+
+    /// Exchanges a code obtained through [`Self::generate_url_for`] with the same `redirect_uri`.
+    pub async fn acquire_token_for<'a, S>(
+        &'a self,
+        code: S,
+        redirect_uri: &'a str,
+    ) -> Result<Token, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.check_redirect_uri(redirect_uri)?;
+        self.acquire_token_with_redirect(code, redirect_uri).await
+    }
+
+    /// The URL and `application/x-www-form-urlencoded` body [`Self::acquire_token_with`]
+    /// would `POST` for `code`, without sending anything.
+    pub fn build_token_request<'a, S>(&'a self, code: S) -> (String, String)
     where
         S: Into<Cow<'a, str>>,
     {
+        self.token_request(code.into(), Cow::Borrowed(&self.config.redirect_uri), None)
+    }
+
+    fn token_request(
+        &self,
+        code: Cow<'_, str>,
+        redirect_uri: Cow<'_, str>,
+        pkce: Option<&PkceVerifier>,
+    ) -> (String, String) {
         let Self {
             secret,
-            config: ClientConfig { redirect_uri, .. },
+            endpoints: Endpoints { token_uri, .. },
             ..
         } = self;
         let WebClientSecret {
             client_id,
-            token_uri,
             client_secret,
             ..
         } = secret;
         let request = TokenRequest {
             client_id: client_id.into(),
-            client_secret: client_secret.into(),
-            code: code.into(),
+            client_secret: client_secret.as_str().into(),
+            code,
             grant_type: AuthorizationCode::new(),
-            redirect_uri: redirect_uri.into(),
+            redirect_uri,
+            code_verifier: pkce.map(|pkce| pkce.verifier().into()),
         };
-        let request = self
-            .client
-            .post(token_uri)
-            .header(
-                http::header::CONTENT_TYPE,
-                "application/x-www-form-urlencoded",
-            )
-            .body(request.urlencoded());
-        let response: Token = request.send().await?.json().await?;
-        Ok(response)
-    }
-
-    pub async fn authorize_with_code<'a, S>(&'a self, code: S) -> reqwest::Result<AuthorizedClient>
+        (token_uri.clone(), request.urlencoded())
+    }
+
+    /// [`Self::acquire_token_with`], also returning the requested scopes the server did not grant.
+    pub async fn acquire_token_checked<'a, S>(
+        &'a self,
+        code: S,
+    ) -> Result<(Token, Vec<DynSingleScope>), Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let token = self.acquire_token_with(code).await?;
+        let missing = self
+            .config
+            .scope
+            .iter()
+            .filter(|s| !token.has_scope(*s))
+            .copied()
+            .collect();
+        Ok((token, missing))
+    }
+
+    pub async fn authorize_with_code<'a, S>(&'a self, code: S) -> Result<AuthorizedClient, Error>
     where
         S: Into<Cow<'a, str>>,
     {
         let token = self.acquire_token_with(code).await?;
-        Ok(self.autorize_with_token(token))
+        Ok(self.authorize_with_token(token))
+    }
+
+    /// Restores a token from `store`, refreshing it if expired,
+    /// or runs the authorization code flow if none is stored
+    /// or it is expired without a refresh token.
+    /// `code` receives the authorization URL and resolves to the returned code.
+    /// The resulting token is saved to `store`.
+    #[tracing::instrument(skip_all)]
+    pub async fn authorize_or_restore<T, F, Fut, E>(
+        &self,
+        store: &T,
+        code: F,
+    ) -> Result<AuthorizedClient, Error>
+    where
+        T: TokenStore,
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+        E: Into<BoxError>,
+    {
+        let client = match store.load().await? {
+            Some(token) if !token.is_expired() => {
+                tracing::debug!("restored token");
+                return Ok(self.authorize_with_token(token));
+            }
+            Some(token) if token.has_refresh_token() => {
+                tracing::debug!("restored token is expired, refreshing");
+                self.authorize_with_token(token).refresh().await?
+            }
+            restored => {
+                if restored.is_some() {
+                    tracing::debug!("restored token is expired and cannot be refreshed");
+                }
+                let code = code(self.generate_url())
+                    .await
+                    .map_err(|e| Error::Custom(e.into()))?;
+                self.authorize_with_code(code).await?
+            }
+        };
+        store.store(client.token()).await?;
+        Ok(client)
+    }
+
+    /// ```no_run
+    /// # async fn f(client: google_oauth::UnauthorizedClient, token: google_oauth::Token) {
+    /// let client = client.authorize_with_token(token);
+    /// # let _ = client;
+    /// # }
+    /// ```
+    #[inline]
+    pub fn authorize_with_token(&self, token: Token) -> AuthorizedClient {
+        AuthorizedClient {
+            endpoints: self.endpoints.clone(),
+            http: Arc::clone(&self.http),
+            ..AuthorizedClient::new(self.secret.clone(), token)
+        }
     }
 
+    #[deprecated(note = "use authorize_with_token")]
     #[inline]
     pub fn autorize_with_token(&self, token: Token) -> AuthorizedClient {
-        AuthorizedClient::new(self.secret.clone(), token)
+        self.authorize_with_token(token)
     }
 }
 
@@ -118,6 +468,14 @@ pub struct UnauthorizedClientBuilder<S = scope::NoScope> {
     redirect_uri: Option<String>,
     scope: S,
     secret: Option<WebClientSecret>,
+    endpoints: Option<Endpoints>,
+    sort_scope: bool,
+    validate_redirect_uri: bool,
+    allow_empty_scope: bool,
+    require_refresh_token: bool,
+    extra_params: Vec<(String, String)>,
+    response_type: ResponseType,
+    prompt: PromptSet,
 }
 
 impl UnauthorizedClientBuilder<scope::NoScope> {
@@ -126,6 +484,14 @@ impl UnauthorizedClientBuilder<scope::NoScope> {
             redirect_uri: None,
             scope: scope::NoScope,
             secret: None,
+            endpoints: None,
+            sort_scope: false,
+            validate_redirect_uri: false,
+            allow_empty_scope: false,
+            require_refresh_token: false,
+            extra_params: Vec::new(),
+            response_type: ResponseType::Code,
+            prompt: PromptSet::new(),
         }
     }
 }
@@ -157,15 +523,40 @@ impl<S1> UnauthorizedClientBuilder<S1> {
             redirect_uri,
             scope,
             secret,
+            endpoints,
+            sort_scope,
+            validate_redirect_uri,
+            allow_empty_scope,
+            require_refresh_token,
+            extra_params,
+            response_type,
+            prompt,
         } = self;
         let scope = scope.with(s2);
         UnauthorizedClientBuilder {
             redirect_uri,
             scope,
             secret,
+            endpoints,
+            sort_scope,
+            validate_redirect_uri,
+            allow_empty_scope,
+            require_refresh_token,
+            extra_params,
+            response_type,
+            prompt,
         }
     }
 
+    /// Removes `s` from the accumulated scope, materializing it as [`SpaceDelimitedScope`].
+    pub fn remove_scope(self, s: &dyn SingleScope) -> UnauthorizedClientBuilder<SpaceDelimitedScope>
+    where
+        S1: Scope,
+    {
+        let scope = self.scope.space_delimited().without(s);
+        self.scope(scope)
+    }
+
     pub fn scope<S>(self, scope: S) -> UnauthorizedClientBuilder<S>
     where
         S: Scope + Clone,
@@ -173,15 +564,40 @@ impl<S1> UnauthorizedClientBuilder<S1> {
         let Self {
             redirect_uri,
             secret,
+            endpoints,
+            sort_scope,
+            validate_redirect_uri,
+            allow_empty_scope,
+            require_refresh_token,
+            extra_params,
+            response_type,
+            prompt,
             ..
         } = self;
         UnauthorizedClientBuilder {
             redirect_uri,
             scope,
             secret,
+            endpoints,
+            sort_scope,
+            validate_redirect_uri,
+            allow_empty_scope,
+            require_refresh_token,
+            extra_params,
+            response_type,
+            prompt,
         }
     }
 
+    /// Replaces the scope with a space-delimited string such as one read from a config file.
+    pub fn scope_str(
+        self,
+        scope: &str,
+    ) -> Result<UnauthorizedClientBuilder<SpaceDelimitedScope>, Error> {
+        let scope: SpaceDelimitedScope = scope.parse().map_err(Error::InvalidScope)?;
+        Ok(self.scope(scope))
+    }
+
     pub fn secret(self, secret: &WebClientSecret) -> Self {
         let secret = secret.clone();
         Self {
@@ -190,25 +606,183 @@ impl<S1> UnauthorizedClientBuilder<S1> {
         }
     }
 
-    pub fn build(self) -> anyhow::Result<UnauthorizedClient>
+    /// Overrides the endpoints otherwise taken from the secret and Google's defaults.
+    pub fn endpoints(self, endpoints: Endpoints) -> Self {
+        Self {
+            endpoints: Some(endpoints),
+            ..self
+        }
+    }
+
+    /// Sorts the scope with [`SpaceDelimitedScope::sorted`] on build,
+    /// so that [`UnauthorizedClient::generate_url`] emits a stable `scope` parameter.
+    pub fn sort_scope(self, value: bool) -> Self {
+        Self {
+            sort_scope: value,
+            ..self
+        }
+    }
+
+    /// Checks on build that the redirect URI is one of [`WebClientSecret::redirect_uris`].
+    /// Disabled by default for applications deriving the URI at runtime.
+    pub fn validate_redirect_uri(self, value: bool) -> Self {
+        Self {
+            validate_redirect_uri: value,
+            ..self
+        }
+    }
+
+    /// Appends `key=value` to the authorization URL after the standard parameters,
+    /// e.g. `enable_granular_consent`. Repeated keys are all kept.
+    pub fn extra_param<K, V>(self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let Self {
+            mut extra_params, ..
+        } = self;
+        extra_params.push((key.into(), value.into()));
+        Self {
+            extra_params,
+            ..self
+        }
+    }
+
+    /// Makes [`UnauthorizedClient::acquire_token_with`] fail with
+    /// [`Error::RefreshTokenNotIssued`] when the response lacks a refresh token.
+    pub fn require_refresh_token(self, value: bool) -> Self {
+        Self {
+            require_refresh_token: value,
+            ..self
+        }
+    }
+
+    /// Selects the `response_type` of the authorization URL, [`ResponseType::Code`] by default.
+    pub fn response_type(self, value: ResponseType) -> Self {
+        Self {
+            response_type: value,
+            ..self
+        }
+    }
+
+    /// Sets the `prompt` of the authorization URL, e.g.
+    /// `Prompt::Consent.into()` to force a new refresh token.
+    pub fn prompt(self, value: PromptSet) -> Self {
+        Self {
+            prompt: value,
+            ..self
+        }
+    }
+
+    /// Lets [`Self::build`] succeed without any scope.
+    pub fn allow_empty_scope(self) -> Self {
+        Self {
+            allow_empty_scope: true,
+            ..self
+        }
+    }
+
+    /// The URL [`UnauthorizedClient::generate_url`] of the built client would return,
+    /// without building it. Fails whenever [`Self::build`] would.
+    pub fn preview_url(&self) -> Result<String, Error>
+    where
+        S1: Scope + Clone,
+    {
+        self.validate()?;
+        let redirect_uri = self.redirect_uri.clone();
+        let redirect_uri = redirect_uri.ok_or(Error::MissingRedirectUri)?;
+        let secret = self.secret.as_ref().ok_or(Error::MissingSecret)?;
+        let auth_uri = match &self.endpoints {
+            Some(endpoints) => &endpoints.auth_uri,
+            None => &secret.auth_uri,
+        };
+        let config = ClientConfig {
+            redirect_uri,
+            scope: self.current_scope(),
+            extra_params: self.extra_params.clone(),
+            response_type: self.response_type,
+            prompt: self.prompt.clone(),
+        };
+        Ok(authorization_url(
+            &secret.client_id,
+            auth_uri,
+            &config,
+            Vec::new(),
+        ))
+    }
+
+    /// The scope [`Self::build`] would configure, sorted if [`Self::sort_scope`] is set,
+    /// e.g. to log it beforehand.
+    pub fn current_scope(&self) -> SpaceDelimitedScope
+    where
+        S1: Scope + Clone,
+    {
+        if self.sort_scope {
+            self.scope.space_delimited().sorted()
+        } else {
+            self.scope.space_delimited()
+        }
+    }
+
+    /// The checks shared by [`Self::build`] and [`Self::preview_url`].
+    fn validate(&self) -> Result<(), Error>
     where
         S1: Scope + Clone,
     {
-        use anyhow::anyhow;
+        let redirect_uri = self
+            .redirect_uri
+            .as_ref()
+            .ok_or(Error::MissingRedirectUri)?;
+        if self.scope.space_delimited().is_empty() && !self.allow_empty_scope {
+            return Err(Error::EmptyScope);
+        }
+        let secret = self.secret.as_ref().ok_or(Error::MissingSecret)?;
+        if self.validate_redirect_uri && !secret.redirect_uris.contains(redirect_uri) {
+            return Err(Error::UnregisteredRedirectUri {
+                redirect_uri: redirect_uri.clone(),
+                allowed: secret.redirect_uris.clone(),
+            });
+        }
+        Ok(())
+    }
 
+    pub fn build(self) -> Result<UnauthorizedClient, Error>
+    where
+        S1: Scope + Clone,
+    {
+        self.validate()?;
+        let scope = self.current_scope();
         let Self {
             redirect_uri,
-            scope,
+            scope: _,
             secret,
+            endpoints,
+            sort_scope: _,
+            validate_redirect_uri: _,
+            allow_empty_scope: _,
+            require_refresh_token,
+            extra_params,
+            response_type,
+            prompt,
         } = self;
-        let redirect_uri = redirect_uri.ok_or_else(|| anyhow!("redirect_uri is required"))?;
-        let scope = scope.space_delimited();
-        let secret = secret.ok_or_else(|| anyhow!("secret is required"))?;
+        let redirect_uri = redirect_uri.ok_or(Error::MissingRedirectUri)?;
+        let secret = secret.ok_or(Error::MissingSecret)?;
         let config = ClientConfig {
             redirect_uri,
             scope,
+            extra_params,
+            response_type,
+            prompt,
+        };
+        let client = UnauthorizedClient {
+            require_refresh_token,
+            ..UnauthorizedClient::new(secret, config)
+        };
+        let client = match endpoints {
+            Some(endpoints) => client.with_endpoints(endpoints),
+            None => client,
         };
-        let client = UnauthorizedClient::new(secret, config);
         Ok(client)
     }
 }
@@ -224,9 +798,11 @@ struct TokenRequest<'a> {
     grant_type: AuthorizationCode,
     #[serde(borrow)]
     redirect_uri: Cow<'a, str>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    code_verifier: Option<Cow<'a, str>>,
 }
 
-impl<'a> TokenRequest<'a> {
+impl TokenRequest<'_> {
     pub fn urlencoded(self) -> String {
         macro_rules! encode_queries {
             [ $($i:ident),+ ] => {
@@ -245,82 +821,498 @@ impl<'a> TokenRequest<'a> {
             code,
             grant_type,
             redirect_uri,
+            code_verifier,
         } = self;
         let grant_type = grant_type.to_string();
         let params = encode_queries![client_id, client_secret, code, grant_type, redirect_uri];
-        params.join("&")
+        let mut body = params.join("&");
+        if let Some(code_verifier) = code_verifier {
+            let [code_verifier] = encode_queries![code_verifier];
+            body.push('&');
+            body.push_str(&code_verifier);
+        }
+        body
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Token {
-    access_token: String,
+    access_token: SecretString,
+    /// some proxies send it as a string
+    #[serde(deserialize_with = "misc::deserialize_u32_lenient")]
     expires_in: u32,
     #[serde(default)]
-    refresh_token: Option<String>,
+    refresh_token: Option<SecretString>,
+    /// may be omitted from refresh responses
+    #[serde(default)]
     scope: SpaceDelimitedScope,
     token_type: Bearer,
+    /// seconds since the UNIX epoch, recorded when the token is received;
+    /// a stored token lacking it counts as expired
+    #[serde(default)]
+    obtained_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id_token: Option<SecretString>,
 }
 
-impl Token {
-    pub fn refresh_with(self, other: Token) -> Self {
-        let Self { refresh_token, .. } = self;
-        Self {
-            refresh_token,
-            ..other
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// [`token_response`] for a freshly issued [`Token`], stamping when it was received.
+fn received_token(response: HttpResponse) -> Result<Token, Error> {
+    let mut token: Token = token_response(response, TransportEndpoint::Token)?;
+    token.obtained_at = unix_now();
+    Ok(token)
+}
+
+/// Parses a token endpoint response, or the error it reports.
+pub(crate) fn token_response<T>(
+    response: HttpResponse,
+    endpoint: TransportEndpoint,
+) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    const SNIPPET_LEN: usize = 200;
+
+    let status = response.status();
+    let content_type = response.headers().get(http::header::CONTENT_TYPE).cloned();
+    let body = response.into_body();
+    // a missing header is tolerated for backends that drop it
+    if let Some(content_type) = content_type.filter(|v| !is_json(v)) {
+        let body = String::from_utf8_lossy(&body);
+        let snippet = match body.char_indices().nth(SNIPPET_LEN) {
+            Some((i, _)) => format!("{}...", &body[..i]),
+            None => body.into_owned(),
+        };
+        return Err(Error::UnexpectedContentType {
+            status,
+            content_type: String::from_utf8_lossy(content_type.as_bytes()).into_owned(),
+            snippet,
+        });
+    }
+    if status.is_success() {
+        return serde_json::from_slice(&body)
+            .map_err(|e| Error::transport(endpoint, TransportPhase::Decode, e));
+    }
+    match serde_json::from_slice::<TokenError>(&body) {
+        Ok(err) if status.is_client_error() => Err(Error::OAuth(err)),
+        _ => {
+            let body = String::from_utf8_lossy(&body).into_owned();
+            Err(Error::Status { status, body })
         }
     }
 }
 
-#[derive(Clone)]
-pub struct AuthorizedClient {
-    #[allow(dead_code)]
-    secret: WebClientSecret,
-    token: Token,
-    inner: reqwest::Client,
+#[tracing::instrument(
+    name = "generate_url",
+    skip_all,
+    fields(
+        scope_count = config.scope.len(),
+        redirect_host = redirect_host(&config.redirect_uri),
+    )
+)]
+fn authorization_url(
+    client_id: &str,
+    auth_uri: &str,
+    config: &ClientConfig,
+    extra: Vec<(&'static str, String)>,
+) -> String {
+    use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+    /// Keeps the unreserved characters and the `:` and `/` of URLs readable,
+    /// while spaces become `%20`.
+    const QUERY_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+        .remove(b'-')
+        .remove(b'.')
+        .remove(b'_')
+        .remove(b'~')
+        .remove(b':')
+        .remove(b'/');
+
+    let params = [("client_id", client_id.to_string())]
+        .into_iter()
+        .chain(config.to_request_params())
+        .chain(extra)
+        .map(|(key, value)| (Cow::Borrowed(key), value));
+    let custom = config.extra_params.iter();
+    // the standard `prompt` takes precedence over a custom one
+    let custom = custom.filter(|(key, _)| config.prompt.is_empty() || key != "prompt");
+    let custom = custom.map(|(key, value)| {
+        (
+            utf8_percent_encode(key, QUERY_COMPONENT).into(),
+            value.clone(),
+        )
+    });
+    let query: Vec<String> = params
+        .chain(custom)
+        .map(|(key, value)| {
+            let value = utf8_percent_encode(&value, QUERY_COMPONENT);
+            format!("{key}={value}")
+        })
+        .collect();
+    tracing::debug!("generated authorization URL");
+    format!("{auth_uri}?{}", query.join("&"))
 }
 
-macro_rules! request_fn {
-    (
-        $(#[$m:meta])*
-        $v:vis $n:ident
-    ) => { ::paste::paste! {
-        $(#[$m])*
-        $v fn [< $n:snake:lower >] (&self, uri: &str) -> ::reqwest::RequestBuilder {
-            self.request(::http::Method::[< $n:snake:upper >], uri)
-        }
-    } };
+/// The `code_challenge` parameters of the authorization URL.
+fn pkce_params(pkce: &PkceVerifier) -> Vec<(&'static str, String)> {
+    vec![
+        ("code_challenge", pkce.challenge()),
+        ("code_challenge_method", PkceVerifier::METHOD.to_string()),
+    ]
 }
 
-impl AuthorizedClient {
-    pub const BASE_URL: &'static str = "https://www.googleapis.com";
+/// Host of the redirect URI, for logging without the full URL.
+fn redirect_host(redirect_uri: &str) -> String {
+    url::Url::parse(redirect_uri)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// `application/json`, or a `+json` type such as `application/problem+json`
+fn is_json(content_type: &http::HeaderValue) -> bool {
+    let Ok(content_type) = content_type.to_str() else {
+        return false;
+    };
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    essence.eq_ignore_ascii_case("application/json")
+        || essence.to_ascii_lowercase().ends_with("+json")
+}
 
+impl Token {
     #[inline]
-    pub fn new(secret: WebClientSecret, token: Token) -> Self {
-        Self {
-            secret,
-            token,
-            inner: reqwest::Client::new(),
-        }
+    pub fn access_token(&self) -> &str {
+        &self.access_token
     }
 
+    /// Only issued for `access_type=offline`, and usually only on the first authorization.
     #[inline]
-    pub fn token(&self) -> &Token {
-        &self.token
+    pub fn refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_deref()
     }
 
-    pub fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}{uri}", Self::BASE_URL);
-        let req = self.inner.request(method, url);
-        self.decorate_request(req)
+    #[inline]
+    pub fn has_refresh_token(&self) -> bool {
+        self.refresh_token.is_some()
     }
 
-    request_fn! {pub get}
-    request_fn! {pub post}
-    request_fn! {pub patch}
+    /// `Bearer <access_token>`, marked sensitive, for authenticating requests
+    /// sent through other clients.
+    /// Fails if the access token contains characters not allowed in a header value.
+    pub fn bearer_header_value(
+        &self,
+    ) -> Result<http::HeaderValue, http::header::InvalidHeaderValue> {
+        let value = format!("Bearer {}", self.access_token);
+        let mut value = http::HeaderValue::try_from(value)?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
+
+    /// Removes the refresh token, e.g. to persist it apart from the short-lived access token.
+    #[inline]
+    pub fn take_refresh_token(&mut self) -> Option<String> {
+        self.refresh_token.take().map(SecretString::into_string)
+    }
+
+    /// Scope actually granted by the authorization server.
+    /// May be narrower than the requested one.
+    #[inline]
+    pub fn scope(&self) -> &SpaceDelimitedScope {
+        &self.scope
+    }
+
+    /// Union of the scopes granted to `self` and `other`, in order of first appearance.
+    ///
+    /// Access tokens themselves cannot be merged: after incremental authorization
+    /// keep the newest one, which Google issues for all scopes granted so far.
+    pub fn merge_scopes(&self, other: &Token) -> SpaceDelimitedScope {
+        let mut seen = std::collections::HashSet::new();
+        self.scope
+            .iter()
+            .chain(other.scope.iter())
+            .filter(|s| seen.insert(**s))
+            .copied()
+            .collect()
+    }
+
+    #[inline]
+    pub fn has_scope(&self, s: &dyn SingleScope) -> bool {
+        self.scope.grants(s)
+    }
+
+    #[inline]
+    pub fn expires_at(&self) -> u64 {
+        self.obtained_at + u64::from(self.expires_in)
+    }
+
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        unix_now() >= self.expires_at()
+    }
+
+    /// Time left until [`Self::expires_at`], or `None` once expired.
+    pub fn expires_in_remaining(&self) -> Option<Duration> {
+        let remaining = self.expires_at().checked_sub(unix_now())?;
+        (remaining > 0).then(|| Duration::from_secs(remaining))
+    }
+
+    /// Takes `other` as the new token, carrying over the refresh token,
+    /// and the scope if `other` has none.
+    pub fn refresh_with(mut self, mut other: Token) -> Self {
+        other.refresh_token = self.refresh_token.take();
+        if other.scope.is_empty() {
+            other.scope = std::mem::take(&mut self.scope);
+        }
+        other
+    }
+}
+
+impl Token {
+    pub fn builder() -> TokenBuilder {
+        TokenBuilder::new()
+    }
+
+    /// An already expired token carrying only `refresh_token`,
+    /// so that the client refreshes it before the first request.
+    pub fn from_refresh_token<S: Into<String>>(refresh_token: S) -> Self {
+        Self::builder().refresh_token(refresh_token).build()
+    }
+}
+
+/// Assembles a [`Token`] from credentials stored elsewhere.
+/// Unset fields leave it expired with an empty access token and scope.
+#[derive(Debug, Clone)]
+pub struct TokenBuilder {
+    token: Token,
+}
+
+impl TokenBuilder {
+    pub fn new() -> Self {
+        let token = Token {
+            access_token: SecretString::default(),
+            expires_in: 0,
+            refresh_token: None,
+            scope: SpaceDelimitedScope::default(),
+            token_type: Bearer::new(),
+            obtained_at: 0,
+            id_token: None,
+        };
+        Self { token }
+    }
+
+    pub fn access_token<S: Into<String>>(mut self, value: S) -> Self {
+        self.token.access_token = value.into().into();
+        self
+    }
+
+    /// Lifetime in seconds counted from [`Self::obtained_at`].
+    pub fn expires_in(mut self, value: u32) -> Self {
+        self.token.expires_in = value;
+        self
+    }
+
+    pub fn refresh_token<S: Into<String>>(mut self, value: S) -> Self {
+        self.token.refresh_token = Some(value.into().into());
+        self
+    }
+
+    pub fn scope(mut self, value: SpaceDelimitedScope) -> Self {
+        self.token.scope = value;
+        self
+    }
+
+    /// Seconds since the UNIX epoch. Defaults to `0`.
+    pub fn obtained_at(mut self, value: u64) -> Self {
+        self.token.obtained_at = value;
+        self
+    }
+
+    pub fn id_token<S: Into<String>>(mut self, value: S) -> Self {
+        self.token.id_token = Some(value.into().into());
+        self
+    }
+
+    pub fn build(self) -> Token {
+        self.token
+    }
+}
+
+impl Default for TokenBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthorizedClient {
+    secret: Option<WebClientSecret>,
+    token: Token,
+    endpoints: Endpoints,
+    base_url: String,
+    inner: reqwest::Client,
+    #[cfg(feature = "middleware")]
+    middleware: Option<reqwest_middleware::ClientWithMiddleware>,
+    http: Arc<dyn HttpClient>,
+}
+
+macro_rules! request_fn {
+    (
+        $(#[$m:meta])*
+        $v:vis $n:ident
+    ) => { ::paste::paste! {
+        $(#[$m])*
+        $v fn [< $n:snake:lower >] (&self, uri: &str) -> ::reqwest::RequestBuilder {
+            self.request(::http::Method::[< $n:snake:upper >], uri)
+        }
+    } };
+}
+
+impl AuthorizedClient {
+    pub const BASE_URL: &'static str = "https://www.googleapis.com";
+
+    #[inline]
+    pub fn new(secret: WebClientSecret, token: Token) -> Self {
+        Self {
+            endpoints: Endpoints::from_secret(&secret),
+            secret: Some(secret),
+            ..Self::from_token(token)
+        }
+    }
+
+    /// A client holding no secret, for making API calls with a token obtained elsewhere.
+    /// [`Self::refresh`] fails with [`Error::MissingSecret`].
+    pub fn from_token(token: Token) -> Self {
+        let inner = reqwest::Client::new();
+        Self {
+            secret: None,
+            token,
+            endpoints: Endpoints::default(),
+            base_url: Self::BASE_URL.to_string(),
+            http: Arc::new(inner.clone()),
+            inner,
+            #[cfg(feature = "middleware")]
+            middleware: None,
+        }
+    }
+
+    /// Replaces the backend used to refresh the token.
+    /// API requests are still built with [`reqwest`].
+    pub fn with_http_client<H: HttpClient>(self, http: H) -> Self {
+        Self {
+            http: Arc::new(http),
+            ..self
+        }
+    }
+
+    /// Replaces the client API requests are built with.
+    pub fn with_reqwest_client(self, inner: reqwest::Client) -> Self {
+        Self { inner, ..self }
+    }
+
+    /// Sends API requests through `middleware` instead of the bare [`reqwest::Client`].
+    #[cfg(feature = "middleware")]
+    pub fn with_middleware(self, middleware: reqwest_middleware::ClientWithMiddleware) -> Self {
+        Self {
+            middleware: Some(middleware),
+            ..self
+        }
+    }
+
+    pub fn with_endpoints(self, endpoints: Endpoints) -> Self {
+        Self { endpoints, ..self }
+    }
+
+    #[inline]
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    /// Sends [`Self::request`]s to `base_url` instead of [`Self::BASE_URL`],
+    /// e.g. a mock server in tests. A trailing `/` is ignored.
+    pub fn with_base_url<S: Into<String>>(self, base_url: S) -> Self {
+        let mut base_url: String = base_url.into();
+        base_url.truncate(base_url.trim_end_matches('/').len());
+        Self { base_url, ..self }
+    }
+
+    #[inline]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    #[inline]
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Scopes of `required` the token does not grant, sorted,
+    /// e.g. to re-request exactly those from the user.
+    pub fn missing_scopes(&self, required: &dyn Scope) -> Vec<DynSingleScope> {
+        let mut missing: Vec<_> = required.difference(&self.token.scope).into_iter().collect();
+        missing.sort();
+        missing
+    }
+
+    pub fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}{uri}", self.base_url);
+        let req = self.inner.request(method, url);
+        self.decorate_request(req)
+    }
+
+    request_fn! {pub get}
+    request_fn! {pub post}
+    request_fn! {pub patch}
     request_fn! {pub put}
     request_fn! {pub delete}
 
+    /// `POST` with `body` serialized as JSON.
+    pub fn post_json<T>(&self, uri: &str, body: &T) -> reqwest::RequestBuilder
+    where
+        T: Serialize + ?Sized,
+    {
+        self.post(uri).json(body)
+    }
+
+    /// `PATCH` with `body` serialized as JSON.
+    pub fn patch_json<T>(&self, uri: &str, body: &T) -> reqwest::RequestBuilder
+    where
+        T: Serialize + ?Sized,
+    {
+        self.patch(uri).json(body)
+    }
+
+    /// Sends a request built with [`Self::request`],
+    /// through the middleware if one is configured.
+    pub async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Error> {
+        let send_error =
+            |e: BoxError| Error::transport(TransportEndpoint::Api, TransportPhase::Send, e);
+        let request = request.build()?;
+        #[cfg(feature = "middleware")]
+        if let Some(middleware) = &self.middleware {
+            let response = middleware.execute(request).await.map_err(|e| match e {
+                reqwest_middleware::Error::Reqwest(e) => send_error(e.into()),
+                reqwest_middleware::Error::Middleware(e) => send_error(e.into()),
+            })?;
+            return Ok(response);
+        }
+        let response = self.inner.execute(request).await;
+        response.map_err(|e| send_error(e.into()))
+    }
+
+    /// The `Authorization` header [`Self::request`] attaches, see [`Token::bearer_header_value`].
+    #[inline]
+    pub fn authorization_header(
+        &self,
+    ) -> Result<http::HeaderValue, http::header::InvalidHeaderValue> {
+        self.token.bearer_header_value()
+    }
+
     #[inline]
     pub(crate) fn decorate_request(
         &self,
@@ -330,22 +1322,62 @@ impl AuthorizedClient {
     }
 
     #[tracing::instrument(skip_all)]
-    pub async fn refresh(self) -> anyhow::Result<Self> {
+    pub async fn refresh(self) -> Result<Self, Error> {
+        let response = self.request_refresh().await?;
+        Ok(self.refreshed_with(response))
+    }
+
+    /// [`Self::refresh`], also reporting how the granted scopes changed, e.g. those
+    /// added through incremental authorization or revoked by the user.
+    #[tracing::instrument(skip_all)]
+    pub async fn refresh_and_report(self) -> Result<(Self, ScopeChange), Error> {
+        let previous = self.token.scope.clone();
+        let client = self.refresh().await?;
+        let new = &client.token.scope;
+        let gained: SpaceDelimitedScope = new.difference(&previous).into_iter().collect();
+        let lost: SpaceDelimitedScope = previous.difference(new).into_iter().collect();
+        if !gained.is_empty() {
+            tracing::info!(%gained, "refresh granted new scopes");
+        }
+        if !lost.is_empty() {
+            tracing::warn!(%lost, "refresh dropped scopes");
+        }
+        let change = ScopeChange {
+            gained: gained.sorted(),
+            lost: lost.sorted(),
+        };
+        Ok((client, change))
+    }
+
+    pub(crate) fn refreshed_with(self, response: Token) -> Self {
+        let Self { token, .. } = self;
+        Self {
+            token: token.refresh_with(response),
+            ..self
+        }
+    }
+
+    /// Sends a single refresh request without consuming `self`.
+    pub(crate) async fn request_refresh(&self) -> Result<Token, Error> {
         use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 
         let Self {
             secret,
             token,
-            inner,
+            endpoints: Endpoints { token_uri, .. },
+            http,
+            ..
         } = self;
-        let WebClientSecret {
+        let Some(WebClientSecret {
             client_id,
             client_secret,
-            token_uri,
             ..
-        } = &secret;
+        }) = &secret
+        else {
+            return Err(Error::MissingSecret);
+        };
         let Some(refresh_token) = &token.refresh_token else {
-            anyhow::bail!("refresh_token is not provided");
+            return Err(Error::MissingRefreshToken);
         };
         let grant_type = RefreshToken::new();
         let client_id = utf8_percent_encode(client_id, NON_ALPHANUMERIC);
@@ -358,41 +1390,1168 @@ impl AuthorizedClient {
             format!("grant_type={grant_type}"),
         ];
         let body = query.join("&");
-        let request = inner
-            .post(token_uri)
-            .header(
-                http::header::CONTENT_TYPE,
-                "application/x-www-form-urlencoded",
-            )
-            .body(body);
-        let response: Token = request
-            .send()
-            .await
-            .inspect_err(|err| {
-                let err = err as &dyn std::error::Error;
-                tracing::error!(err, "could not send request");
-            })?
-            .json()
-            .await
-            .inspect_err(|err| {
-                let err = err as &dyn std::error::Error;
-                tracing::error!(err, "could not parse response body as JSON");
-            })?;
-        let token = token.refresh_with(response);
-        Ok(Self {
-            secret,
-            token,
-            inner,
+        let request = form_request(token_uri, body)?;
+        let response = http.execute(request).await.map_err(|err| {
+            tracing::error!(err, "could not send request");
+            Error::transport(TransportEndpoint::Token, TransportPhase::Send, err)
+        })?;
+        received_token(response).inspect_err(|err| {
+            let err = err as &dyn std::error::Error;
+            tracing::error!(err, "could not parse token response");
         })
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
-#[error("insufficient scope to perform request")]
-pub struct InsufficientScopeError(());
+/// Exchanges a persisted `refresh_token` for a fresh [`Token`] at `secret`'s `token_uri`,
+/// without an [`UnauthorizedClient`] or [`AuthorizedClient`] around.
+/// The returned token carries `refresh_token` over, as [`Token::refresh_with`] does.
+#[tracing::instrument(skip_all)]
+pub async fn refresh_token(
+    secret: &WebClientSecret,
+    refresh_token: &str,
+    http: Option<reqwest::Client>,
+) -> Result<Token, Error> {
+    let client = AuthorizedClient::new(secret.clone(), Token::from_refresh_token(refresh_token));
+    let client = match http {
+        Some(http) => client.with_http_client(http),
+        None => client,
+    };
+    let client = client.refresh().await?;
+    Ok(client.token().clone())
+}
+
+/// Returned by [`AuthorizedClient::refresh_and_report`]; both sides are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ScopeChange {
+    /// Granted by the refreshed token but not the previous one.
+    pub gained: SpaceDelimitedScope,
+    /// Granted by the previous token but no longer by the refreshed one.
+    pub lost: SpaceDelimitedScope,
+}
+
+impl ScopeChange {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.gained.is_empty() && self.lost.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InsufficientScopeError {
+    /// Scopes the request asked for, one or all of which are needed.
+    pub required: Vec<&'static str>,
+    pub granted: SpaceDelimitedScope,
+    all: bool,
+}
 
 impl InsufficientScopeError {
-    pub(crate) fn new() -> Self {
-        Self(())
+    /// Needs one of `required`.
+    pub fn any(required: Vec<&'static str>, granted: SpaceDelimitedScope) -> Self {
+        Self {
+            required,
+            granted,
+            all: false,
+        }
+    }
+
+    /// Needs every one of `required`.
+    pub fn all(required: Vec<&'static str>, granted: SpaceDelimitedScope) -> Self {
+        Self {
+            required,
+            granted,
+            all: true,
+        }
+    }
+}
+
+impl fmt::Display for InsufficientScopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            required,
+            granted,
+            all,
+        } = self;
+        let need = if *all { "all of" } else { "one of" };
+        write!(
+            f,
+            "insufficient scope: need {need} [{}], have [{}]",
+            required.join(", "),
+            granted.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for InsufficientScopeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::{Calendar, CalendarEvents, CalendarReadonly};
+    use crate::test_util::{
+        authorized, secret, token, token_response, unauthorized, unauthorized_builder,
+        MockHttpClient,
+    };
+
+    #[test]
+    fn test_token_has_scope() {
+        let requested = crate::combine_scope![calendar.readonly, calendar.events].space_delimited();
+        let token: Token = serde_json::from_value(token_response(&CalendarReadonly)).unwrap();
+        assert!(token.scope().scope().is_subset(&requested.scope()));
+        assert!(token.has_scope(&CalendarReadonly));
+        assert!(!token.has_scope(&CalendarEvents));
+        assert!(!token.has_scope(&Calendar));
+    }
+
+    #[test]
+    fn test_token_accessors() {
+        let mut token = token(&Calendar).refresh_token("refresh").build();
+        assert_eq!(token.access_token(), "access");
+        assert_eq!(token.refresh_token(), Some("refresh"));
+        assert_eq!(token.take_refresh_token().as_deref(), Some("refresh"));
+        assert_eq!(token.refresh_token(), None);
+        assert_eq!(token.take_refresh_token(), None);
+        assert_eq!(token.access_token(), "access");
+    }
+
+    #[test]
+    fn test_token_from_refresh_token() {
+        let token = Token::from_refresh_token("refresh");
+        assert!(token.is_expired());
+        assert_eq!(token.refresh_token(), Some("refresh"));
+        assert_eq!(token.access_token(), "");
+
+        let token = Token::builder()
+            .access_token("access")
+            .expires_in(3599)
+            .obtained_at(unix_now())
+            .scope(vec![Calendar.as_dyn()].into())
+            .build();
+        assert!(!token.is_expired());
+        assert!(token.has_scope(&Calendar));
+        assert_eq!(token.refresh_token(), None);
+    }
+
+    #[test]
+    fn test_token_merge_scopes() {
+        let a = token(&CalendarReadonly)
+            .scope(vec![CalendarReadonly.as_dyn(), CalendarEvents.as_dyn()].into())
+            .build();
+        let b = token(&CalendarEvents)
+            .scope(vec![CalendarEvents.as_dyn(), Calendar.as_dyn()].into())
+            .build();
+        let expected: SpaceDelimitedScope = vec![
+            CalendarReadonly.as_dyn(),
+            CalendarEvents.as_dyn(),
+            Calendar.as_dyn(),
+        ]
+        .into();
+        assert_eq!(a.merge_scopes(&b), expected);
+        assert_eq!(a.merge_scopes(&a), *a.scope());
+    }
+
+    #[test]
+    fn test_token_expires_in_string() {
+        let mut payload = token_response(&Calendar);
+        for expires_in in [serde_json::json!(3600), serde_json::json!("3600")] {
+            payload["expires_in"] = expires_in;
+            let token: Token = serde_json::from_value(payload.clone()).unwrap();
+            assert_eq!(token.expires_in, 3600);
+        }
+        payload["expires_in"] = "soon".into();
+        let result = serde_json::from_value::<Token>(payload);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_refresh_with_missing_scope() {
+        let token = token(&Calendar)
+            .refresh_token("refresh")
+            .scope(vec![Calendar.as_dyn(), CalendarEvents.as_dyn()].into())
+            .build();
+        let expected = token.scope().clone();
+        let refreshed: Token = serde_json::from_value(serde_json::json!({
+            "access_token": "refreshed",
+            "expires_in": 3599,
+            "token_type": "Bearer"
+        }))
+        .unwrap();
+        assert!(refreshed.scope().is_empty());
+        let token = token.refresh_with(refreshed);
+        assert_eq!(token.access_token(), "refreshed");
+        assert_eq!(token.refresh_token(), Some("refresh"));
+        assert_eq!(token.scope(), &expected);
+    }
+
+    #[tokio::test]
+    async fn test_authorize_or_restore_unrefreshable() {
+        use crate::store::{FileTokenStore, TokenStore};
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, token_response(&Calendar));
+        let client = unauthorized(http.clone());
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileTokenStore::new(dir.path().join("token.json"));
+        let expired = Token::builder()
+            .access_token("stale")
+            .expires_in(3599)
+            .scope(Calendar.space_delimited())
+            .build();
+        store.store(&expired).await.unwrap();
+
+        let authorized = client
+            .authorize_or_restore(&store, |url| async move {
+                assert!(url.starts_with("https://accounts.google.com/"), "{url}");
+                Ok::<_, std::convert::Infallible>("code".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(authorized.token().access_token(), "access");
+        let requests = http.take_requests();
+        assert_eq!(requests.len(), 1);
+        let body = String::from_utf8_lossy(requests[0].body());
+        assert!(body.contains("&code=code&"), "{body}");
+        let stored = store.load().await.unwrap().unwrap();
+        assert_eq!(stored.access_token(), "access");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_fn() {
+        use axum::extract::Form;
+        use std::collections::HashMap;
+
+        let router = axum::Router::new().route(
+            "/token",
+            axum::routing::post(|Form(form): Form<HashMap<String, String>>| async move {
+                assert_eq!(form["grant_type"], "refresh_token");
+                assert_eq!(form["refresh_token"], "refresh/1");
+                assert_eq!(form["client_id"], "client_id");
+                axum::Json(token_response(&Calendar))
+            }),
+        );
+        let addr = crate::test_util::serve(router).await;
+        let mut secret = secret();
+        secret.token_uri = format!("http://{addr}/token");
+
+        let token = refresh_token(&secret, "refresh/1", Some(reqwest::Client::new()))
+            .await
+            .unwrap();
+        assert_eq!(token.access_token(), "access");
+        assert_eq!(token.refresh_token(), Some("refresh/1"));
+        assert!(!token.is_expired());
+        assert!(token.has_scope(&Calendar));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_and_report() {
+        let http = MockHttpClient::default();
+        let refreshed = |scope: String| {
+            serde_json::json!({
+                "access_token": "refreshed",
+                "expires_in": 3599,
+                "scope": scope,
+                "token_type": "Bearer"
+            })
+        };
+        let scope = format!("{} {}", CalendarReadonly::STR, CalendarEvents::STR);
+        http.push_json(http::StatusCode::OK, refreshed(scope));
+        http.push_json(http::StatusCode::OK, refreshed(Calendar::STR.to_string()));
+        http.push_json(
+            http::StatusCode::OK,
+            refreshed(CalendarEvents::STR.to_string()),
+        );
+        let token = Token::builder()
+            .access_token("access")
+            .refresh_token("refresh")
+            .scope(CalendarReadonly.space_delimited())
+            .build();
+        let client = AuthorizedClient::new(secret(), token).with_http_client(http);
+
+        let (client, change) = client.refresh_and_report().await.unwrap();
+        assert_eq!(change.gained, vec![CalendarEvents.as_dyn()].into());
+        assert!(change.lost.is_empty());
+        let (client, change) = client.refresh_and_report().await.unwrap();
+        assert_eq!(change.gained, vec![Calendar.as_dyn()].into());
+        // the full calendar scope still grants the narrower ones
+        assert!(change.lost.is_empty());
+        let (client, change) = client.refresh_and_report().await.unwrap();
+        assert!(change.gained.is_empty());
+        assert_eq!(change.lost, vec![Calendar.as_dyn()].into());
+        assert_eq!(client.token().access_token(), "refreshed");
+    }
+
+    #[test]
+    fn test_builder_remove_scope() {
+        let client = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(Calendar)
+            .add_scope(CalendarReadonly)
+            .add_scope(CalendarEvents)
+            .remove_scope(&CalendarReadonly)
+            .secret(&secret())
+            .build()
+            .unwrap();
+        let expected: SpaceDelimitedScope = vec![Calendar.as_dyn(), CalendarEvents.as_dyn()].into();
+        assert_eq!(client.config.scope, expected);
+    }
+
+    #[test]
+    fn test_builder_sort_scope() {
+        let a = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(CalendarReadonly)
+            .add_scope(Calendar)
+            .add_scope(CalendarEvents)
+            .secret(&secret())
+            .sort_scope(true)
+            .build()
+            .unwrap();
+        let b = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(CalendarEvents)
+            .add_scope(CalendarReadonly)
+            .add_scope(Calendar)
+            .secret(&secret())
+            .sort_scope(true)
+            .build()
+            .unwrap();
+        assert_eq!(a.generate_url(), b.generate_url());
+    }
+
+    #[test]
+    fn test_builder_preview_url() {
+        let builder = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(CalendarEvents)
+            .add_scope(Calendar)
+            .sort_scope(true)
+            .extra_param("enable_granular_consent", "true");
+        let err = builder.preview_url().unwrap_err();
+        assert!(matches!(err, Error::MissingSecret), "{err:?}");
+
+        let builder = builder.secret(&secret());
+        let preview = builder.preview_url().unwrap();
+        assert_eq!(preview, builder.clone().build().unwrap().generate_url());
+
+        let endpoints = Endpoints::default().auth_uri("http://127.0.0.1:8080/auth");
+        let builder = builder.endpoints(endpoints);
+        let preview = builder.preview_url().unwrap();
+        assert!(
+            preview.starts_with("http://127.0.0.1:8080/auth?"),
+            "{preview}"
+        );
+        assert_eq!(preview, builder.build().unwrap().generate_url());
+
+        let empty = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .secret(&secret());
+        let err = empty.preview_url().unwrap_err();
+        assert!(matches!(err, Error::EmptyScope), "{err:?}");
+
+        let unregistered = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:3000/callback")
+            .add_scope(Calendar)
+            .secret(&secret())
+            .validate_redirect_uri(true);
+        let err = unregistered.preview_url().unwrap_err();
+        assert!(
+            matches!(err, Error::UnregisteredRedirectUri { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_generate_url_prompt() {
+        let builder = unauthorized_builder();
+        let url = builder.clone().build().unwrap().generate_url();
+        assert!(!url.contains("prompt="), "{url}");
+
+        let prompt = PromptSet::from(Prompt::Consent)
+            .with(Prompt::SelectAccount)
+            .unwrap();
+        let url = builder
+            .clone()
+            .prompt(prompt.clone())
+            .build()
+            .unwrap()
+            .generate_url();
+        assert!(
+            url.ends_with("&access_type=offline&prompt=consent%20select_account"),
+            "{url}"
+        );
+
+        let builder = builder.extra_param("prompt", "none");
+        let url = builder.clone().build().unwrap().generate_url();
+        assert!(url.ends_with("&access_type=offline&prompt=none"), "{url}");
+        let url = builder.prompt(prompt).build().unwrap().generate_url();
+        assert_eq!(url.matches("prompt=").count(), 1, "{url}");
+        assert!(url.ends_with("&prompt=consent%20select_account"), "{url}");
+    }
+
+    #[test]
+    fn test_builder_current_scope() {
+        let builder = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(CalendarEvents)
+            .add_scope(Calendar)
+            .secret(&secret());
+        let scope = builder.current_scope();
+        assert_eq!(
+            scope.to_string(),
+            format!("{} {}", CalendarEvents::STR, Calendar::STR)
+        );
+        assert_eq!(builder.clone().build().unwrap().config.scope, scope);
+
+        let builder = builder.sort_scope(true);
+        let scope = builder.current_scope();
+        assert_eq!(
+            scope.to_string(),
+            format!("{} {}", Calendar::STR, CalendarEvents::STR)
+        );
+        assert_eq!(builder.build().unwrap().config.scope, scope);
+    }
+
+    #[test]
+    fn test_token_is_expired() {
+        let mut token: Token = serde_json::from_value(token_response(&Calendar)).unwrap();
+        // stored without a timestamp, so its age is unknown
+        assert!(token.is_expired());
+        token.obtained_at = unix_now();
+        assert!(!token.is_expired());
+        token.obtained_at -= 3600;
+        assert!(token.is_expired());
+    }
+
+    #[test]
+    fn test_token_expires_in_remaining() {
+        let token = Token::builder()
+            .expires_in(3600)
+            .obtained_at(unix_now())
+            .build();
+        let remaining = token.expires_in_remaining().unwrap();
+        assert!(remaining <= Duration::from_secs(3600), "{remaining:?}");
+        assert!(remaining >= Duration::from_secs(3590), "{remaining:?}");
+
+        let expired = Token::builder()
+            .expires_in(3600)
+            .obtained_at(unix_now() - 3600)
+            .build();
+        assert_eq!(expired.expires_in_remaining(), None);
+        assert_eq!(Token::builder().build().expires_in_remaining(), None);
+    }
+
+    #[test]
+    fn test_build_without_redirect_uri() {
+        let result = UnauthorizedClient::builder()
+            .add_scope(Calendar)
+            .secret(&secret())
+            .build();
+        assert!(matches!(result, Err(Error::MissingRedirectUri)));
+    }
+
+    #[test]
+    fn test_build_without_secret() {
+        let result = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(Calendar)
+            .build();
+        assert!(matches!(result, Err(Error::MissingSecret)));
+    }
+
+    #[tokio::test]
+    async fn test_from_token() {
+        let token = Token::builder()
+            .access_token("access")
+            .refresh_token("refresh")
+            .build();
+        let client = AuthorizedClient::from_token(token);
+        let request = client.get("/calendar/v3/users/me/calendarList");
+        let request = request.build().unwrap();
+        assert_eq!(
+            request.headers()[http::header::AUTHORIZATION],
+            "Bearer access"
+        );
+        let err = client.refresh().await.err().unwrap();
+        assert!(matches!(err, Error::MissingSecret), "{err:?}");
+    }
+
+    #[test]
+    fn test_post_json() {
+        let client = authorized(&Calendar);
+        let body = serde_json::json!({ "summary": "Meeting" });
+        for request in [
+            client.post_json("/calendar/v3/calendars", &body),
+            client.patch_json("/calendar/v3/calendars/primary", &body),
+        ] {
+            let request = request.build().unwrap();
+            let headers = request.headers();
+            assert_eq!(headers[http::header::CONTENT_TYPE], "application/json");
+            assert_eq!(headers[http::header::AUTHORIZATION], "Bearer access");
+            let sent = request.body().and_then(|b| b.as_bytes()).unwrap();
+            assert_eq!(
+                serde_json::from_slice::<serde_json::Value>(sent).unwrap(),
+                body
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_base_url() {
+        let token = Token::builder().access_token("access").build();
+        let client = AuthorizedClient::new(secret(), token);
+        assert_eq!(client.base_url(), AuthorizedClient::BASE_URL);
+        let client = client.with_base_url("http://127.0.0.1:8080/");
+        assert_eq!(client.base_url(), "http://127.0.0.1:8080");
+        let request = client
+            .get("/calendar/v3/users/me/calendarList")
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "http://127.0.0.1:8080/calendar/v3/users/me/calendarList"
+        );
+        assert_eq!(
+            request.headers()[http::header::AUTHORIZATION],
+            "Bearer access"
+        );
+    }
+
+    #[test]
+    fn test_authorization_header() {
+        let token = Token::builder().access_token("ya29.access").build();
+        let value = token.bearer_header_value().unwrap();
+        assert_eq!(value, "Bearer ya29.access");
+        assert!(value.is_sensitive());
+
+        let client = AuthorizedClient::new(secret(), token);
+        let request = client.get("/userinfo").build().unwrap();
+        assert_eq!(
+            client.authorization_header().unwrap(),
+            request.headers()[http::header::AUTHORIZATION]
+        );
+
+        let token = Token::builder().access_token("ya29.\naccess").build();
+        assert!(token.bearer_header_value().is_err());
+    }
+
+    #[test]
+    fn test_missing_scopes() {
+        let token = Token::builder()
+            .scope(CalendarReadonly.space_delimited())
+            .build();
+        let client = AuthorizedClient::new(secret(), token);
+        assert_eq!(client.missing_scopes(&Calendar), vec![Calendar.as_dyn()]);
+        assert!(client.missing_scopes(&CalendarReadonly).is_empty());
+
+        let required = Calendar.with(CalendarEvents).with(CalendarReadonly);
+        assert_eq!(
+            client.missing_scopes(&required),
+            vec![Calendar.as_dyn(), CalendarEvents.as_dyn()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_from_secret_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("client_secret.json");
+        let secret = ClientSecret { web: secret() };
+        std::fs::write(&path, serde_json::to_vec(&secret).unwrap()).unwrap();
+        let config = ClientConfig::new(
+            "http://localhost:8080/oauth2/callback",
+            Calendar.space_delimited(),
+        );
+        let client = UnauthorizedClient::from_secret_file(&path, config)
+            .await
+            .unwrap();
+        let url = url::Url::parse(&client.generate_url()).unwrap();
+        let client_id = url.query_pairs().find(|(k, _)| k == "client_id");
+        assert_eq!(client_id.unwrap().1, "client_id");
+
+        let config = client.config.clone();
+        let err = UnauthorizedClient::from_secret_file(dir.path().join("missing.json"), config)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Io(_)), "{err:?}");
+    }
+
+    #[test]
+    fn test_client_config_serde() {
+        let config = ClientConfig::new(
+            "http://localhost:8080/oauth2/callback",
+            Calendar.with(CalendarEvents).space_delimited(),
+        );
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "redirect_uri": "http://localhost:8080/oauth2/callback",
+                "scope": format!("{} {}", Calendar::STR, CalendarEvents::STR)
+            })
+        );
+        let de: ClientConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(de, config);
+
+        let client = UnauthorizedClient::from_parts(secret(), de);
+        assert_eq!(client.config, config);
+    }
+
+    #[test]
+    fn test_client_config_from_env() {
+        // prefixed so as not to race with other tests
+        std::env::set_var("CONFIG_TEST_OAUTH_REDIRECT_URI", "http://localhost:8080/cb");
+        std::env::set_var(
+            "CONFIG_TEST_OAUTH_SCOPE",
+            format!("{} {}", Calendar::STR, CalendarEvents::STR),
+        );
+        let config = ClientConfig::from_env(Some("CONFIG_TEST")).unwrap();
+        assert_eq!(config.redirect_uri, "http://localhost:8080/cb");
+        assert_eq!(
+            config.scope,
+            vec![Calendar.as_dyn(), CalendarEvents.as_dyn()].into()
+        );
+
+        std::env::set_var("CONFIG_TEST_OAUTH_SCOPE", "https://example.com/unknown");
+        let err = ClientConfig::from_env(Some("CONFIG_TEST")).unwrap_err();
+        assert!(matches!(err, Error::InvalidScope(_)), "{err:?}");
+
+        std::env::remove_var("CONFIG_TEST_OAUTH_SCOPE");
+        let err = ClientConfig::from_env(Some("CONFIG_TEST")).unwrap_err();
+        assert!(matches!(err, Error::EmptyScope), "{err:?}");
+        std::env::remove_var("CONFIG_TEST_OAUTH_REDIRECT_URI");
+    }
+
+    #[test]
+    fn test_to_request_params() {
+        let client = unauthorized_builder().build().unwrap();
+        let params = client.config.to_request_params();
+        let keys: Vec<_> = params.iter().map(|(k, _)| *k).collect();
+        assert_eq!(
+            keys,
+            vec!["redirect_uri", "scope", "response_type", "access_type"]
+        );
+        assert_eq!(params[1].1, Calendar::STR);
+        assert_eq!(
+            client.generate_url(),
+            "https://accounts.google.com/o/oauth2/auth?client_id=client_id\
+             &redirect_uri=http://localhost:8080/oauth2/callback\
+             &scope=https://www.googleapis.com/auth/calendar\
+             &response_type=code&access_type=offline"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_type() {
+        let builder = unauthorized_builder();
+        for (response_type, expected, access_type) in [
+            (ResponseType::Code, "code", true),
+            (ResponseType::Token, "token", false),
+            (ResponseType::CodeToken, "code token", true),
+        ] {
+            let client = builder
+                .clone()
+                .response_type(response_type)
+                .build()
+                .unwrap();
+            let url = url::Url::parse(&client.generate_url()).unwrap();
+            let params: Vec<_> = url.query_pairs().into_owned().collect();
+            let value = params.iter().find(|(k, _)| k == "response_type").unwrap();
+            assert_eq!(value.1, expected);
+            assert_eq!(params.iter().any(|(k, _)| k == "access_type"), access_type);
+        }
+
+        let client = builder.response_type(ResponseType::Token).build().unwrap();
+        let err = client.acquire_token_with("code").await.unwrap_err();
+        assert!(
+            matches!(err, Error::CodeNotRequested(ResponseType::Token)),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_builder_scope_str() {
+        let payload = format!("{} {}", Calendar::STR, CalendarEvents::STR);
+        let client = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .secret(&secret())
+            .scope_str(&payload)
+            .unwrap()
+            .build()
+            .unwrap();
+        let scope: Vec<_> = client.config.scope.iter().copied().collect();
+        assert_eq!(scope, vec![Calendar.as_dyn(), CalendarEvents.as_dyn()]);
+
+        let payload = format!("{} https://www.googleapis.com/auth/unknown", Calendar::STR);
+        let result = UnauthorizedClient::builder().scope_str(&payload);
+        let Err(Error::InvalidScope(message)) = result else {
+            panic!("expected InvalidScope");
+        };
+        assert!(
+            message.contains("https://www.googleapis.com/auth/unknown"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_generate_url_with_nonce() {
+        let client = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(crate::scope::Openid)
+            .secret(&secret())
+            .build()
+            .unwrap();
+        let url = client.generate_url_with_nonce("n-0S6_WzA2Mj");
+        assert!(url.ends_with("&nonce=n-0S6_WzA2Mj"), "{url}");
+        assert!(!client.generate_url().contains("nonce="));
+    }
+
+    #[test]
+    fn test_generate_url_with_scope() {
+        let client = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(CalendarReadonly)
+            .secret(&secret())
+            .build()
+            .unwrap();
+        let url = client.generate_url_with_scope(&Calendar.with(CalendarEvents));
+        let expected = "&scope=https://www.googleapis.com/auth/calendar\
+             %20https://www.googleapis.com/auth/calendar.events&";
+        assert!(url.contains(expected), "{url}");
+        assert!(!url.contains(CalendarReadonly::STR), "{url}");
+        let parsed = url::Url::parse(&url).unwrap();
+        let scope = parsed.query_pairs().find(|(k, _)| k == "scope").unwrap().1;
+        assert_eq!(scope, format!("{} {}", Calendar::STR, CalendarEvents::STR));
+        let url = client.generate_url();
+        assert!(url.contains(&format!("&scope={}&", CalendarReadonly::STR)));
+    }
+
+    #[tokio::test]
+    async fn test_multiple_redirect_uris() {
+        let mut secret = secret();
+        secret.redirect_uris = vec![
+            "https://example.com/oauth2/callback".to_string(),
+            "http://localhost:8080/oauth2/callback".to_string(),
+        ];
+        let http = MockHttpClient::default();
+        let client = unauthorized_builder()
+            .secret(&secret)
+            .build()
+            .unwrap()
+            .with_http_client(http.clone());
+        assert_eq!(
+            client.redirect_uris(),
+            [
+                "http://localhost:8080/oauth2/callback",
+                "https://example.com/oauth2/callback"
+            ]
+        );
+
+        for redirect_uri in client.redirect_uris() {
+            let url = url::Url::parse(&client.generate_url_for(redirect_uri).unwrap()).unwrap();
+            let param = url.query_pairs().find(|(k, _)| k == "redirect_uri");
+            assert_eq!(param.unwrap().1, redirect_uri);
+
+            http.push_json(http::StatusCode::OK, token_response(&Calendar));
+            client
+                .acquire_token_for("code", redirect_uri)
+                .await
+                .unwrap();
+            let requests = http.take_requests();
+            let body = std::str::from_utf8(requests[0].body()).unwrap();
+            let sent = url::form_urlencoded::parse(body.as_bytes())
+                .find(|(k, _)| k == "redirect_uri")
+                .unwrap();
+            assert_eq!(sent.1, redirect_uri);
+        }
+
+        let unlisted = "https://evil.example.com/callback";
+        let err = client.generate_url_for(unlisted).unwrap_err();
+        assert!(
+            matches!(&err, Error::UnregisteredRedirectUri { allowed, .. } if allowed.len() == 2),
+            "{err:?}"
+        );
+        let err = client
+            .acquire_token_for("code", unlisted)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::UnregisteredRedirectUri { .. }),
+            "{err:?}"
+        );
+        assert!(http.take_requests().is_empty());
+    }
+
+    #[test]
+    fn test_generate_url_extra_params() {
+        let client = unauthorized_builder()
+            .extra_param("enable_granular_consent", "true")
+            .extra_param("hd", "example.com")
+            .extra_param("hd", "example.org")
+            .build()
+            .unwrap();
+        let url = client.generate_url();
+        let expected = "&access_type=offline\
+            &enable_granular_consent=true\
+            &hd=example.com&hd=example.org";
+        assert!(url.ends_with(expected), "{url}");
+    }
+
+    #[test]
+    fn test_build_empty_scope() {
+        let builder = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .secret(&secret());
+        let result = builder.clone().build();
+        assert!(matches!(result, Err(Error::EmptyScope)));
+        assert!(builder.clone().allow_empty_scope().build().is_ok());
+        assert!(builder.add_scope(Calendar).build().is_ok());
+    }
+
+    #[test]
+    fn test_build_validate_redirect_uri() {
+        let builder = UnauthorizedClient::builder()
+            .add_scope(Calendar)
+            .secret(&secret())
+            .validate_redirect_uri(true);
+        let result = builder
+            .clone()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .build();
+        assert!(result.is_ok());
+
+        let err = builder
+            .redirect_uri("http://localhost:3000/callback")
+            .build()
+            .err()
+            .unwrap();
+        let Error::UnregisteredRedirectUri { allowed, .. } = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(allowed, &vec!["http://localhost:8080/oauth2/callback"]);
+        assert!(err.to_string().contains("http://localhost:3000/callback"));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_oauth_error() {
+        let router = axum::Router::new().route(
+            "/token",
+            axum::routing::post(|| async {
+                let body = axum::Json(serde_json::json!({
+                    "error": "invalid_grant",
+                    "error_description": "Bad Request"
+                }));
+                (http::StatusCode::BAD_REQUEST, body)
+            }),
+        );
+        let addr = crate::test_util::serve(router).await;
+        let mut secret = secret();
+        secret.token_uri = format!("http://{addr}/token");
+        let client = unauthorized_builder().secret(&secret).build().unwrap();
+        let err = client.acquire_token_with("code").await.unwrap_err();
+        let Error::OAuth(err) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(err.error, "invalid_grant");
+        assert_eq!(err.error_description.as_deref(), Some("Bad Request"));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_connection_reset() {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                // RST instead of FIN on close
+                stream.set_linger(Some(std::time::Duration::ZERO)).unwrap();
+                drop(stream);
+            }
+        });
+        let mut secret = secret();
+        secret.token_uri = format!("http://{addr}/token");
+        let client = unauthorized_builder().secret(&secret).build().unwrap();
+        let err = client.acquire_token_with("code").await.unwrap_err();
+        assert_eq!(err.to_string(), "failed to send request to token endpoint");
+        assert!(
+            matches!(
+                err,
+                Error::Transport {
+                    endpoint: TransportEndpoint::Token,
+                    phase: TransportPhase::Send,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_malformed_response() {
+        let http = MockHttpClient::default();
+        http.push_json(
+            http::StatusCode::OK,
+            serde_json::json!({ "token_type": "Bearer", "expires_in": "soon" }),
+        );
+        let client = unauthorized(http);
+        let err = client.acquire_token_with("code").await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::Transport {
+                    endpoint: TransportEndpoint::Token,
+                    phase: TransportPhase::Decode,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_html_response() {
+        let router = axum::Router::new().route(
+            "/token",
+            axum::routing::post(|| async {
+                let body = format!("<html><body>{}</body></html>", "x".repeat(1000));
+                (http::StatusCode::BAD_GATEWAY, axum::response::Html(body))
+            }),
+        );
+        let addr = crate::test_util::serve(router).await;
+        let mut secret = secret();
+        secret.token_uri = format!("http://{addr}/token");
+        let client = unauthorized_builder().secret(&secret).build().unwrap();
+        let err = client.acquire_token_with("code").await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("502 Bad Gateway"), "{message}");
+        assert!(message.contains("text/html"), "{message}");
+        let Error::UnexpectedContentType { snippet, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert!(snippet.starts_with("<html><body>xxx"), "{snippet}");
+        assert!(snippet.len() < 300, "{snippet}");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_with_endpoints() {
+        let router = axum::Router::new().route(
+            "/token",
+            axum::routing::post(|headers: http::HeaderMap, body: String| async move {
+                assert!(body.contains("code=code"), "{body}");
+                assert_eq!(headers[http::header::ACCEPT], "application/json");
+                axum::Json(token_response(&Calendar))
+            }),
+        );
+        let addr = crate::test_util::serve(router).await;
+        let endpoints = Endpoints::default().token_uri(format!("http://{addr}/token"));
+        let client = unauthorized_builder()
+            .endpoints(endpoints.clone())
+            .build()
+            .unwrap();
+        let client = client.authorize_with_code("code").await.unwrap();
+        assert_eq!(client.token().access_token, "access");
+        assert_eq!(client.endpoints(), &endpoints);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_with_mock_backend() {
+        let http = MockHttpClient::default();
+        let mut response = token_response(&Calendar);
+        response["refresh_token"] = "refresh".into();
+        http.push_json(http::StatusCode::OK, response);
+        let client = unauthorized(http.clone());
+        let token = client.acquire_token_with("code/1").await.unwrap();
+        assert_eq!(token.access_token, "access");
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+
+        let requests = http.take_requests();
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        assert_eq!(request.method(), http::Method::POST);
+        assert_eq!(request.uri(), "https://oauth2.googleapis.com/token");
+        let body = std::str::from_utf8(request.body()).unwrap();
+        assert!(body.contains("code=code%2F1"), "{body}");
+        assert!(body.contains("grant_type=authorization%5Fcode"), "{body}");
+        let headers = request.headers();
+        assert_eq!(headers[http::header::ACCEPT], "application/json");
+        assert_eq!(
+            headers[http::header::CONTENT_LENGTH],
+            request.body().len().to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_require_refresh_token() {
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, token_response(&Calendar));
+        let client = unauthorized_builder()
+            .require_refresh_token(true)
+            .build()
+            .unwrap()
+            .with_http_client(http);
+        let err = client.acquire_token_with("code").await.unwrap_err();
+        assert!(matches!(err, Error::RefreshTokenNotIssued), "{err:?}");
+        assert!(err.to_string().contains("prompt=consent"), "{err}");
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_auth_flow_tracing() {
+        let http = MockHttpClient::default();
+        http.push_json(
+            http::StatusCode::OK,
+            serde_json::json!({
+                "access_token": "access-c4f2",
+                "expires_in": 3599,
+                "refresh_token": "refresh-9e1b",
+                "scope": Calendar::STR,
+                "token_type": "Bearer"
+            }),
+        );
+        let client = unauthorized(http);
+        client.generate_url();
+        client.acquire_token_with("code-7d3a").await.unwrap();
+
+        assert!(logs_contain(
+            "generate_url{scope_count=1 redirect_host=\"localhost\"}"
+        ));
+        assert!(logs_contain("acquire_token{grant_type=authorization_code}"));
+        assert!(logs_contain("outcome=\"ok\""));
+        for secret in [
+            "code-7d3a",
+            "access-c4f2",
+            "refresh-9e1b",
+            "client_secret",
+            "callback",
+        ] {
+            assert!(!logs_contain(secret), "{secret} leaked");
+        }
+    }
+
+    #[test]
+    fn test_build_token_request() {
+        let client = unauthorized_builder().build().unwrap();
+        let (url, body) = client.build_token_request("4/0Ad+x");
+        assert_eq!(url, "https://oauth2.googleapis.com/token");
+        assert!(body.contains("code=4%2F0Ad%2Bx"), "{body}");
+        let params: Vec<(String, String)> = url::form_urlencoded::parse(body.as_bytes())
+            .into_owned()
+            .collect();
+        assert!(params.contains(&("grant_type".to_string(), "authorization_code".to_string())));
+        assert!(params.contains(&("code".to_string(), "4/0Ad+x".to_string())));
+        assert!(!body.contains("code_verifier="), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_with_pkce() {
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, token_response(&Calendar));
+        let client = unauthorized(http.clone());
+        // RFC 7636 Appendix B
+        let pkce = PkceVerifier::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        let url = client.generate_url_with_pkce(&pkce);
+        assert!(
+            url.ends_with(
+                "&code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM\
+                 &code_challenge_method=S256"
+            ),
+            "{url}"
+        );
+        assert!(!client.generate_url().contains("code_challenge"));
+
+        let token = client.acquire_token_with_pkce("code", &pkce).await.unwrap();
+        assert_eq!(token.access_token(), "access");
+        let requests = http.take_requests();
+        let params: Vec<(String, String)> = url::form_urlencoded::parse(requests[0].body())
+            .into_owned()
+            .collect();
+        let verifier = ("code_verifier".to_string(), pkce.verifier().to_string());
+        assert!(params.contains(&verifier), "{params:?}");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_with_redirect() {
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, token_response(&Calendar));
+        let client = unauthorized(http.clone());
+        client
+            .acquire_token_with_redirect("code", "http://127.0.0.1:9000/cb")
+            .await
+            .unwrap();
+
+        let requests = http.take_requests();
+        let body = std::str::from_utf8(requests[0].body()).unwrap();
+        let expected = "redirect_uri=http%3A%2F%2F127%2E0%2E0%2E1%3A9000%2Fcb";
+        assert!(body.contains(expected), "{body}");
+        assert!(!body.contains("8080"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_token_checked() {
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, token_response(&CalendarReadonly));
+        let client = UnauthorizedClient::builder()
+            .redirect_uri("http://localhost:8080/oauth2/callback")
+            .add_scope(CalendarReadonly)
+            .add_scope(CalendarEvents)
+            .secret(&secret())
+            .build()
+            .unwrap()
+            .with_http_client(http);
+        let (token, missing) = client.acquire_token_checked("code").await.unwrap();
+        assert!(token.has_scope(&CalendarReadonly));
+        assert_eq!(missing, vec![CalendarEvents.as_dyn()]);
+    }
+
+    #[cfg(feature = "middleware")]
+    #[tokio::test]
+    async fn test_send_with_middleware() {
+        use std::sync::Mutex;
+
+        use futures::future::BoxFuture;
+
+        type Response = reqwest_middleware::Result<reqwest::Response>;
+
+        /// pins the higher-ranked signature `Middleware` is implemented for
+        fn middleware<F>(f: F) -> F
+        where
+            F: for<'a> Fn(
+                reqwest::Request,
+                &'a mut http::Extensions,
+                reqwest_middleware::Next<'a>,
+            ) -> BoxFuture<'a, Response>,
+        {
+            f
+        }
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = {
+            let seen = Arc::clone(&seen);
+            middleware(move |req, _, _| {
+                let auth = req.headers()[http::header::AUTHORIZATION].clone();
+                let path = req.url().path().to_string();
+                seen.lock()
+                    .unwrap()
+                    .push((req.method().clone(), path, auth));
+                Box::pin(async { Ok(http::Response::new("{}").into()) })
+            })
+        };
+        let middleware = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(log)
+            .build();
+        let client = authorized(&Calendar).with_middleware(middleware);
+        let response = client
+            .send(client.get("/calendar/v3/colors"))
+            .await
+            .unwrap();
+        assert_eq!(response.text().await.unwrap(), "{}");
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            *seen,
+            [(
+                http::Method::GET,
+                "/calendar/v3/colors".to_string(),
+                http::HeaderValue::from_static("Bearer access")
+            )]
+        );
     }
 }
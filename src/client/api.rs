@@ -0,0 +1,42 @@
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, GoogleApiError, TransportEndpoint, TransportPhase};
+
+/// Joins `key=value` pairs with the values percent-encoded.
+#[cfg(any(feature = "calendar", feature = "drive"))]
+pub(crate) fn encode_query(params: Vec<(&str, String)>) -> String {
+    use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+    let params: Vec<String> = params
+        .into_iter()
+        .map(|(key, value)| format!("{key}={}", utf8_percent_encode(&value, NON_ALPHANUMERIC)))
+        .collect();
+    params.join("&")
+}
+
+/// Passes a successful response through,
+/// turning any other into [`Error::Api`] or [`Error::Status`].
+pub(crate) async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, Error> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response
+        .text()
+        .await
+        .map_err(|e| Error::transport(TransportEndpoint::Api, TransportPhase::Send, e))?;
+    match GoogleApiError::from_body(&body) {
+        Some(e) => Err(e.into()),
+        None => Err(Error::Status { status, body }),
+    }
+}
+
+pub(crate) async fn read_json<T: DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T, Error> {
+    let response = check_status(response).await?;
+    response
+        .json()
+        .await
+        .map_err(|e| Error::transport(TransportEndpoint::Api, TransportPhase::Decode, e))
+}
@@ -0,0 +1,134 @@
+use std::borrow::Cow;
+
+use rand::distributions::{Alphanumeric, DistString};
+
+use crate::error::Error;
+use crate::route::CallbackError;
+
+use super::{pkce_params, AuthorizedClient, PkceVerifier, UnauthorizedClient};
+
+/// One run of the authorization code flow, holding the `state` sent to the server
+/// (and the PKCE verifier, if any) until the redirect comes back.
+#[derive(Clone)]
+pub struct AuthorizationRequest<'a> {
+    client: &'a UnauthorizedClient,
+    state: String,
+    pkce: Option<PkceVerifier>,
+}
+
+impl UnauthorizedClient {
+    /// Starts the authorization code flow with a freshly generated `state`.
+    pub fn begin_authorization(&self) -> AuthorizationRequest<'_> {
+        let state = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+        AuthorizationRequest {
+            client: self,
+            state,
+            pkce: None,
+        }
+    }
+}
+
+impl AuthorizationRequest<'_> {
+    #[inline]
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// Protects this run with a freshly generated PKCE verifier.
+    pub fn with_pkce(self) -> Self {
+        Self {
+            pkce: Some(PkceVerifier::new()),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn pkce(&self) -> Option<&PkceVerifier> {
+        self.pkce.as_ref()
+    }
+
+    /// The URL to send the user to.
+    pub fn url(&self) -> String {
+        let mut params = vec![("state", self.state.clone())];
+        if let Some(pkce) = &self.pkce {
+            params.extend(pkce_params(pkce));
+        }
+        self.client
+            .generate_url_with_params(&self.client.config, params)
+    }
+
+    /// Exchanges `code` once the redirect returned the `state` this request was started with.
+    pub async fn complete<'s, S>(self, state: &str, code: S) -> Result<AuthorizedClient, Error>
+    where
+        S: Into<Cow<'s, str>>,
+    {
+        if state != self.state {
+            return Err(CallbackError::StateMismatch.into());
+        }
+        let redirect_uri = Cow::Borrowed(self.client.config.redirect_uri.as_str());
+        let token = self
+            .client
+            .exchange_code(code.into(), redirect_uri, self.pkce.as_ref())
+            .await?;
+        Ok(self.client.authorize_with_token(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::Calendar;
+    use crate::test_util::{token_response, unauthorized, MockHttpClient};
+
+    #[tokio::test]
+    async fn test_begin_authorization() {
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, token_response(&Calendar));
+        let client = unauthorized(http.clone());
+        let request = client.begin_authorization();
+        let state = request.state().to_string();
+        assert_eq!(state.len(), 32);
+        assert!(request.url().ends_with(&format!("&state={state}")));
+        assert_ne!(client.begin_authorization().state(), state);
+
+        let Err(err) = request.clone().complete("forged", "code").await else {
+            panic!("state mismatch was accepted");
+        };
+        assert!(
+            matches!(err, Error::Callback(CallbackError::StateMismatch)),
+            "{err:?}"
+        );
+        assert!(http.take_requests().is_empty());
+
+        let authorized = request.complete(&state, "code").await.unwrap();
+        assert_eq!(authorized.token().access_token(), "access");
+        let body = http.take_requests()[0].body().to_vec();
+        assert!(!String::from_utf8(body).unwrap().contains("code_verifier"));
+    }
+
+    #[tokio::test]
+    async fn test_begin_authorization_with_pkce() {
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, token_response(&Calendar));
+        let client = unauthorized(http.clone());
+        let request = client.begin_authorization().with_pkce();
+        let pkce = request.pkce().unwrap().clone();
+        let state = request.state().to_string();
+        let url = request.url();
+        assert!(
+            url.ends_with(&format!(
+                "&state={state}&code_challenge={}&code_challenge_method=S256",
+                pkce.challenge()
+            )),
+            "{url}"
+        );
+
+        request.complete(&state, "code").await.unwrap();
+        let requests = http.take_requests();
+        let params: Vec<(String, String)> = url::form_urlencoded::parse(requests[0].body())
+            .into_owned()
+            .collect();
+        let verifier = ("code_verifier".to_string(), pkce.verifier().to_string());
+        assert!(params.contains(&verifier), "{params:?}");
+    }
+}
@@ -1,18 +1,94 @@
 // FIXME
 #![allow(dead_code)]
 
-use crate::WebClientSecret;
-
-use super::{AuthorizedClient, InsufficientScopeError, Token};
-
-macro_rules! contain_scope {
-    ( [
-        $( $i0:ident $(. $i:ident)* ),+
-    ] in $s:expr ) => { ::paste::paste! { {
-        use $crate::scope::{Scope, SingleScope};
-        let scope = Scope::scope($s);
-        $( scope.contains(&SingleScope::as_dyn( & $crate::scope::[< $i0:camel $($i:camel)* >] )) )&&+
-    } } };
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{Error, GoogleApiError};
+use crate::{require_scopes, WebClientSecret};
+
+use super::api::{check_status, encode_query, read_json};
+use super::{AuthorizedClient, InsufficientScopeError, RetryPolicy, Token};
+
+/// Defines a fieldless enum whose `as_str`, `FromStr`, `Display` and serde impls
+/// all share one variant-to-string mapping.
+/// `$err` is constructed from the rejected input when parsing fails.
+macro_rules! string_enum {
+    (
+        $(#[$m:meta])*
+        $v:vis enum $name:ident: $err:ident {
+            $( $variant:ident = $s:literal ),+ $(,)?
+        }
+    ) => {
+        $(#[$m])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        $v enum $name {
+            $( $variant ),+
+        }
+
+        impl $name {
+            pub const ALL: &'static [Self] = &[ $( Self::$variant ),+ ];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $( Self::$variant => $s ),+
+                }
+            }
+
+            /// The accepted strings joined with `, `, for error messages.
+            fn expected() -> String {
+                Self::ALL.iter().map(Self::as_str).collect::<Vec<_>>().join(", ")
+            }
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $err;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.trim() {
+                    $( $s => Ok(Self::$variant), )+
+                    _ => Err($err(s.to_string())),
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                let s = <::std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                s.parse().map_err(::serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+/// Encodes an ID as a path segment, keeping the unreserved characters.
+fn encode_id(id: &str) -> String {
+    use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+    const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+        .remove(b'-')
+        .remove(b'.')
+        .remove(b'_')
+        .remove(b'~');
+    utf8_percent_encode(id, SEGMENT).to_string()
 }
 
 #[derive(Clone, Copy)]
@@ -27,7 +103,7 @@ impl AuthorizedClient {
     }
 }
 
-impl<'a> CalendarClient<'a> {
+impl CalendarClient<'_> {
     pub const BASE_PATH: &'static str = "/calendar/v3";
 
     pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
@@ -35,9 +111,33 @@ impl<'a> CalendarClient<'a> {
         self.inner.request(method, &uri)
     }
 
+    pub(crate) fn post_json<T>(&self, uri: &str, body: &T) -> reqwest::RequestBuilder
+    where
+        T: Serialize + ?Sized,
+    {
+        let uri = format!("{}{}", Self::BASE_PATH, uri);
+        self.inner.post_json(&uri, body)
+    }
+
+    pub(crate) fn patch_json<T>(&self, uri: &str, body: &T) -> reqwest::RequestBuilder
+    where
+        T: Serialize + ?Sized,
+    {
+        let uri = format!("{}{}", Self::BASE_PATH, uri);
+        self.inner.patch_json(&uri, body)
+    }
+
     #[inline]
-    fn secret(&self) -> &WebClientSecret {
-        &self.inner.secret
+    pub(crate) async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, Error> {
+        self.inner.send(request).await
+    }
+
+    #[inline]
+    fn secret(&self) -> Option<&WebClientSecret> {
+        self.inner.secret.as_ref()
     }
 
     #[inline]
@@ -47,7 +147,15 @@ impl<'a> CalendarClient<'a> {
 }
 
 mod calendar_list {
+    use serde::{Deserialize, Serialize};
+
     use super::*;
+    use crate::client::pagination::{Page, Paginated};
+
+    pub use list::{
+        InvalidMaxResults, ParameterMinAccessRole, ParseMinAccessRoleError,
+        Response as CalendarList,
+    };
 
     #[derive(Clone, Copy)]
     pub struct Client<'a> {
@@ -61,7 +169,7 @@ mod calendar_list {
         }
     }
 
-    impl<'a> Client<'a> {
+    impl Client<'_> {
         pub const BASE_PATH: &'static str = "/users/me/calendarList";
 
         pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
@@ -70,7 +178,7 @@ mod calendar_list {
         }
 
         #[inline]
-        fn secret(&self) -> &WebClientSecret {
+        fn secret(&self) -> Option<&WebClientSecret> {
             self.inner.secret()
         }
 
@@ -80,10 +188,38 @@ mod calendar_list {
         }
     }
 
+    /// https://developers.google.com/calendar/api/v3/reference/calendarList#resource
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CalendarListEntry {
+        pub id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub summary: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub summary_override: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub time_zone: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub color_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub background_color: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub foreground_color: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub access_role: Option<String>,
+        #[serde(default)]
+        pub primary: bool,
+        #[serde(default)]
+        pub hidden: bool,
+        #[serde(default)]
+        pub selected: bool,
+    }
+
     mod list {
         use std::borrow::Cow;
-        use std::fmt;
-        use std::str::FromStr;
+        use std::ops::RangeInclusive;
 
         use serde::{Deserialize, Serialize};
 
@@ -98,11 +234,8 @@ mod calendar_list {
 
         impl<'a> Client<'a> {
             pub fn list(&self) -> Result<Request<'a>, InsufficientScopeError> {
-                if contain_scope!([calendar, calendar.readonly] in &self.token().scope) {
-                    Ok(Request::new(*self))
-                } else {
-                    Err(InsufficientScopeError::new())
-                }
+                require_scopes!(any [calendar, calendar.readonly] in &self.token().scope)?;
+                Ok(Request::new(*self))
             }
         }
 
@@ -125,12 +258,12 @@ mod calendar_list {
                 }
             }
 
-            pub fn param_max_results(self, value: u8) -> Self {
+            pub fn param_max_results(self, value: u16) -> Result<Self, InvalidMaxResults> {
                 let Self { client, parameters } = self;
-                Self {
+                Ok(Self {
                     client,
-                    parameters: parameters.max_results(value),
-                }
+                    parameters: parameters.max_results(value)?,
+                })
             }
 
             pub fn param_min_access_role(self, value: ParameterMinAccessRole) -> Self {
@@ -179,7 +312,31 @@ mod calendar_list {
                 }
             }
 
-            pub async fn send(self) -> reqwest::Result<Response> {
+            pub async fn send(self) -> Result<Response, Error> {
+                read_json(self.send_raw().await?).await
+            }
+
+            /// Sends the request without reading the body,
+            /// leaving the status and headers such as `X-RateLimit-*` to the caller.
+            pub async fn send_raw(self) -> Result<reqwest::Response, Error> {
+                let client = self.client.inner;
+                client.send(self.into_request()).await
+            }
+
+            /// [`Self::send`] waiting out `429`/`503` responses with `Retry-After`,
+            /// see [`AuthorizedClient::send_with_rate_limit_retry`].
+            pub async fn send_with_rate_limit_retry(
+                self,
+                policy: RetryPolicy,
+            ) -> Result<Response, Error> {
+                let client = self.client.inner.inner;
+                let response = client
+                    .send_with_rate_limit_retry(self.into_request(), policy)
+                    .await?;
+                read_json(response).await
+            }
+
+            fn into_request(self) -> reqwest::RequestBuilder {
                 let Self { client, parameters } = self;
                 let query = parameters.into_query();
                 let uri = if query.is_empty() {
@@ -187,23 +344,17 @@ mod calendar_list {
                 } else {
                     format!("?{}", query)
                 };
-                let res: Response = client
-                    .request(http::Method::GET, &uri)
-                    .send()
-                    .await?
-                    .json()
-                    .await?;
-                Ok(res)
+                client.request(http::Method::GET, &uri)
             }
         }
 
         #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
         pub struct Parameters {
-            max_results: Option<u8>,
+            max_results: Option<u16>,
             min_access_role: Option<ParameterMinAccessRole>,
             page_token: Option<String>,
-            show_deleted: bool,
-            show_hidden: bool,
+            show_deleted: Option<bool>,
+            show_hidden: Option<bool>,
             sync_token: Option<String>,
         }
 
@@ -212,11 +363,17 @@ mod calendar_list {
                 Self::default()
             }
 
-            pub fn max_results(self, value: u8) -> Self {
-                Self {
+            pub const MAX_RESULTS: RangeInclusive<u16> = 1..=250;
+
+            /// Fails unless `value` is within [`Self::MAX_RESULTS`].
+            pub fn max_results(self, value: u16) -> Result<Self, InvalidMaxResults> {
+                if !Self::MAX_RESULTS.contains(&value) {
+                    return Err(InvalidMaxResults(value));
+                }
+                Ok(Self {
                     max_results: Some(value),
                     ..self
-                }
+                })
             }
 
             pub fn min_access_role(self, value: ParameterMinAccessRole) -> Self {
@@ -238,14 +395,14 @@ mod calendar_list {
 
             pub fn show_deleted(self, value: bool) -> Self {
                 Self {
-                    show_deleted: value,
+                    show_deleted: Some(value),
                     ..self
                 }
             }
 
             pub fn show_hidden(self, value: bool) -> Self {
                 Self {
-                    show_hidden: value,
+                    show_hidden: Some(value),
                     ..self
                 }
             }
@@ -260,9 +417,8 @@ mod calendar_list {
                 }
             }
 
-            pub fn into_query(self) -> String {
-                use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
-
+            /// Parameters that are set, in the order of the API reference.
+            pub fn to_params(&self) -> Vec<(&'static str, String)> {
                 let Self {
                     max_results,
                     min_access_role,
@@ -272,67 +428,120 @@ mod calendar_list {
                     sync_token,
                 } = self;
                 let params = [
-                    max_results.map(|v| format!("maxResults={v}")),
-                    min_access_role.map(|v| format!("minAccessRole={v}")),
-                    page_token.map(|v| {
-                        let encoded = utf8_percent_encode(&v, NON_ALPHANUMERIC);
-                        format!("pageToken={encoded}")
-                    }),
-                    Some(format!("showDeleted={show_deleted}")),
-                    Some(format!("showHidden={show_hidden}")),
-                    sync_token.map(|v| {
-                        let encoded = utf8_percent_encode(&v, NON_ALPHANUMERIC);
-                        format!("syncToken={encoded}")
-                    }),
+                    ("maxResults", max_results.map(|v| v.to_string())),
+                    ("minAccessRole", min_access_role.map(|v| v.to_string())),
+                    ("pageToken", page_token.clone()),
+                    ("showDeleted", show_deleted.map(|v| v.to_string())),
+                    ("showHidden", show_hidden.map(|v| v.to_string())),
+                    ("syncToken", sync_token.clone()),
                 ];
-                let params: Vec<String> = params.into_iter().flatten().collect();
-                params.join("&")
+                params
+                    .into_iter()
+                    .filter_map(|(key, value)| Some((key, value?)))
+                    .collect()
+            }
+
+            pub fn into_query(self) -> String {
+                encode_query(self.to_params())
             }
         }
 
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        string_enum! {
+            pub enum ParameterMinAccessRole: ParseMinAccessRoleError {
+                FreeBusyReader = "freeBusyReader",
+                Owner = "owner",
+                Reader = "reader",
+                Writer = "writer",
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+        #[error("maxResults must be within 1..=250, got {0}")]
+        pub struct InvalidMaxResults(pub(crate) u16);
+
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+        #[error(
+            "invalid minAccessRole {:?}, expected one of {}",
+            .0,
+            ParameterMinAccessRole::expected()
+        )]
+        pub struct ParseMinAccessRoleError(pub(crate) String);
+
+        /// https://developers.google.com/calendar/api/v3/reference/calendarList/list#response
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
         #[serde(rename_all = "camelCase")]
-        pub enum ParameterMinAccessRole {
-            FreeBusyReader,
-            Owner,
-            Reader,
-            Writer,
+        pub struct Response {
+            #[serde(default)]
+            pub items: Vec<CalendarListEntry>,
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub next_page_token: Option<String>,
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub next_sync_token: Option<String>,
         }
 
-        impl ParameterMinAccessRole {
-            pub fn as_str(&self) -> &'static str {
-                match self {
-                    Self::FreeBusyReader => "freeBusyReader",
-                    Self::Owner => "owner",
-                    Self::Reader => "reader",
-                    Self::Writer => "writer",
+        impl From<Response> for Page<CalendarListEntry> {
+            fn from(value: Response) -> Self {
+                let Response {
+                    items,
+                    next_page_token,
+                    ..
+                } = value;
+                Page {
+                    items,
+                    next_page_token,
                 }
             }
         }
 
-        impl FromStr for ParameterMinAccessRole {
-            // FIXME
-            type Err = &'static str;
-
-            fn from_str(s: &str) -> Result<Self, Self::Err> {
-                match s.trim() {
-                    "freeBusyReader" => Ok(Self::FreeBusyReader),
-                    "owner" => Ok(Self::Owner),
-                    "reader" => Ok(Self::Reader),
-                    "writer" => Ok(Self::Writer),
-                    _ => Err("received invalid str"),
+        impl Client<'_> {
+            /// Lists every entry, or only those changed since `previous_sync_token`,
+            /// returning them with the token for the next call.
+            /// Fails with [`Error::SyncTokenExpired`] when the token must be dropped
+            /// in favor of a full sync with `None`.
+            pub async fn sync(
+                &self,
+                previous_sync_token: Option<String>,
+            ) -> Result<(Vec<CalendarListEntry>, String), Error> {
+                let mut request = self.list()?;
+                if let Some(sync_token) = previous_sync_token {
+                    request = request.param_sync_token(sync_token);
+                }
+                let mut entries = Vec::new();
+                loop {
+                    let response = request.clone().send_raw().await?;
+                    if response.status() == http::StatusCode::GONE {
+                        return Err(Error::SyncTokenExpired);
+                    }
+                    let Response {
+                        items,
+                        next_page_token,
+                        next_sync_token,
+                    } = read_json(response).await?;
+                    entries.extend(items);
+                    match (next_page_token, next_sync_token) {
+                        (Some(page_token), _) => request = request.param_page_token(page_token),
+                        (None, Some(sync_token)) => return Ok((entries, sync_token)),
+                        (None, None) => {
+                            let message = "response has neither nextPageToken nor nextSyncToken";
+                            return Err(Error::Custom(message.into()));
+                        }
+                    }
                 }
             }
         }
 
-        impl fmt::Display for ParameterMinAccessRole {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                f.write_str(self.as_str())
+        impl Paginated for Request<'_> {
+            type Item = CalendarListEntry;
+            type Error = Error;
+
+            fn with_page_token(self, token: String) -> Self {
+                self.param_page_token(token)
             }
-        }
 
-        // FIXME
-        pub type Response = serde_json::Value;
+            async fn fetch_page(self) -> Result<Page<CalendarListEntry>, Error> {
+                Ok(self.send().await?.into())
+            }
+        }
     }
 
     mod get {
@@ -347,11 +556,8 @@ mod calendar_list {
 
         impl<'a> Client<'a> {
             pub fn get(&self, calendar_id: &str) -> Result<Request<'a>, InsufficientScopeError> {
-                if contain_scope!([calendar, calendar.readonly] in &self.token().scope) {
-                    Ok(Request::new(*self, calendar_id))
-                } else {
-                    Err(InsufficientScopeError::new())
-                }
+                require_scopes!(any [calendar, calendar.readonly] in &self.token().scope)?;
+                Ok(Request::new(*self, calendar_id))
             }
         }
 
@@ -374,16 +580,11 @@ mod calendar_list {
                 }
             }
 
-            pub async fn send(self) -> reqwest::Result<Response> {
+            pub async fn send(self) -> Result<Response, Error> {
                 let Self { client, parameters } = self;
                 let uri = parameters.into_uri();
-                let res: Response = client
-                    .request(http::Method::GET, &uri)
-                    .send()
-                    .await?
-                    .json()
-                    .await?;
-                Ok(res)
+                let request = client.request(http::Method::GET, &uri);
+                read_json(client.inner.send(request).await?).await
             }
         }
 
@@ -404,7 +605,1898 @@ mod calendar_list {
             }
         }
 
-        // FIXME
-        pub type Response = serde_json::Value;
+        pub type Response = CalendarListEntry;
+    }
+}
+
+mod events {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    pub struct Client<'a> {
+        pub(crate) inner: CalendarClient<'a>,
+    }
+
+    impl<'a> CalendarClient<'a> {
+        #[inline]
+        pub fn events(&self) -> Client<'a> {
+            Client { inner: *self }
+        }
+    }
+
+    impl Client<'_> {
+        pub const BASE_PATH: &'static str = "/calendars";
+
+        pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.request(method, &uri)
+        }
+
+        pub(crate) fn patch_json<T>(&self, uri: &str, body: &T) -> reqwest::RequestBuilder
+        where
+            T: Serialize + ?Sized,
+        {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.patch_json(&uri, body)
+        }
+
+        #[inline]
+        fn token(&self) -> &Token {
+            self.inner.token()
+        }
+    }
+
+    /// https://developers.google.com/calendar/api/v3/reference/events#resource
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Event {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub status: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub html_link: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub summary: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub location: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub start: Option<EventDateTime>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub end: Option<EventDateTime>,
+        /// `RRULE`, `EXRULE`, `RDATE` and `EXDATE` lines of a recurring event
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub recurrence: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub attendees: Vec<Attendee>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub conference_data: Option<ConferenceData>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub reminders: Option<Reminders>,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Attendee {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub email: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub display_name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub response_status: Option<ResponseStatus>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        pub optional: bool,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        pub organizer: bool,
+        /// Whether this entry is the calendar the event was read from
+        #[serde(default, rename = "self", skip_serializing_if = "std::ops::Not::not")]
+        pub is_self: bool,
+    }
+
+    string_enum! {
+        pub enum ResponseStatus: ParseResponseStatusError {
+            NeedsAction = "needsAction",
+            Declined = "declined",
+            Tentative = "tentative",
+            Accepted = "accepted",
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+    #[error(
+        "invalid responseStatus {:?}, expected one of {}",
+        .0,
+        ResponseStatus::expected()
+    )]
+    pub struct ParseResponseStatusError(pub(crate) String);
+
+    /// https://developers.google.com/calendar/api/v3/reference/events#conferenceData
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ConferenceData {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub conference_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub entry_points: Vec<EntryPoint>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub notes: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct EntryPoint {
+        /// `video`, `phone`, `sip` or `more`
+        pub entry_point_type: String,
+        pub uri: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub label: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Reminders {
+        /// Whether the calendar's default reminders apply
+        pub use_default: bool,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub overrides: Vec<ReminderOverride>,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct ReminderOverride {
+        /// `email` or `popup`
+        pub method: String,
+        pub minutes: u32,
+    }
+
+    /// Either `date` for all-day events or `date_time` is set.
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct EventDateTime {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub date: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub date_time: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub time_zone: Option<String>,
+    }
+
+    /// Which of the two forms an [`EventDateTime`] takes.
+    #[cfg(feature = "chrono")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum EventTime {
+        AllDay(chrono::NaiveDate),
+        At(chrono::DateTime<chrono::Utc>),
+    }
+
+    #[cfg(feature = "chrono")]
+    impl EventDateTime {
+        /// Parses `date_time` if set, else `date`; `Ok(None)` when neither is.
+        pub fn to_chrono(&self) -> Result<Option<EventTime>, chrono::ParseError> {
+            if let Some(date_time) = &self.date_time {
+                let date_time = chrono::DateTime::parse_from_rfc3339(date_time)?;
+                return Ok(Some(EventTime::At(date_time.to_utc())));
+            }
+            self.date
+                .as_deref()
+                .map(|date| date.parse().map(EventTime::AllDay))
+                .transpose()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Parameters {
+        calendar_id: String,
+        event_id: String,
+    }
+
+    impl Parameters {
+        pub fn new(calendar_id: &str, event_id: &str) -> Self {
+            Self {
+                calendar_id: calendar_id.to_string(),
+                event_id: event_id.to_string(),
+            }
+        }
+
+        pub fn into_uri(self) -> String {
+            let Self {
+                calendar_id,
+                event_id,
+            } = self;
+            let calendar_id = encode_id(&calendar_id);
+            let event_id = encode_id(&event_id);
+            format!("/{calendar_id}/events/{event_id}")
+        }
+    }
+
+    mod get {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/events/get
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) parameters: Parameters,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn get(
+                &self,
+                calendar_id: &str,
+                event_id: &str,
+            ) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!(any [
+                    calendar,
+                    calendar.readonly,
+                    calendar.events,
+                    calendar.events.readonly
+                ] in &self.token().scope)?;
+                let parameters = Parameters::new(calendar_id, event_id);
+                Ok(Request {
+                    client: *self,
+                    parameters,
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self { client, parameters } = self;
+                client.request(http::Method::GET, &parameters.into_uri())
+            }
+
+            pub async fn send(self) -> Result<Event, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+
+    mod delete {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/events/delete
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) parameters: Parameters,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn delete(
+                &self,
+                calendar_id: &str,
+                event_id: &str,
+            ) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!(any [calendar, calendar.events] in &self.token().scope)?;
+                let parameters = Parameters::new(calendar_id, event_id);
+                Ok(Request {
+                    client: *self,
+                    parameters,
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self { client, parameters } = self;
+                client.request(http::Method::DELETE, &parameters.into_uri())
+            }
+
+            pub async fn send(self) -> Result<(), Error> {
+                let client = self.client.inner;
+                check_status(client.send(self.into_request()).await?).await?;
+                Ok(())
+            }
+        }
+    }
+
+    mod patch {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/events/patch
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) parameters: Parameters,
+            pub(crate) body: Event,
+        }
+
+        impl<'a> Client<'a> {
+            /// Only the fields set in `partial` are updated.
+            pub fn patch(
+                &self,
+                calendar_id: &str,
+                event_id: &str,
+                partial: Event,
+            ) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!(any [calendar, calendar.events] in &self.token().scope)?;
+                let parameters = Parameters::new(calendar_id, event_id);
+                Ok(Request {
+                    client: *self,
+                    parameters,
+                    body: partial,
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self {
+                    client,
+                    parameters,
+                    body,
+                } = self;
+                client.patch_json(&parameters.into_uri(), &body)
+            }
+
+            pub async fn send(self) -> Result<Event, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+
+    mod quick_add {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/events/quickAdd
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) calendar_id: String,
+            pub(crate) text: String,
+        }
+
+        impl<'a> Client<'a> {
+            /// Creates an event from natural-language `text` such as `"Lunch tomorrow at noon"`.
+            pub fn quick_add(
+                &self,
+                calendar_id: &str,
+                text: &str,
+            ) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!(any [calendar, calendar.events] in &self.token().scope)?;
+                Ok(Request {
+                    client: *self,
+                    calendar_id: calendar_id.to_string(),
+                    text: text.to_string(),
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self {
+                    client,
+                    calendar_id,
+                    text,
+                } = self;
+                let query = encode_query(vec![("text", text)]);
+                let uri = format!("/{}/events/quickAdd?{query}", encode_id(&calendar_id));
+                client.request(http::Method::POST, &uri)
+            }
+
+            pub async fn send(self) -> Result<Event, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+}
+
+mod calendars {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    pub struct Client<'a> {
+        pub(crate) inner: CalendarClient<'a>,
+    }
+
+    impl<'a> CalendarClient<'a> {
+        #[inline]
+        pub fn calendars(&self) -> Client<'a> {
+            Client { inner: *self }
+        }
+    }
+
+    impl Client<'_> {
+        pub const BASE_PATH: &'static str = "/calendars";
+
+        pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.request(method, &uri)
+        }
+
+        pub(crate) fn post_json<T>(&self, uri: &str, body: &T) -> reqwest::RequestBuilder
+        where
+            T: Serialize + ?Sized,
+        {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.post_json(&uri, body)
+        }
+
+        #[inline]
+        fn token(&self) -> &Token {
+            self.inner.token()
+        }
+    }
+
+    /// https://developers.google.com/calendar/api/v3/reference/calendars#resource
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Calendar {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub id: Option<String>,
+        pub summary: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub time_zone: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub location: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Parameters {
+        calendar_id: String,
+    }
+
+    impl Parameters {
+        pub fn new(calendar_id: &str) -> Self {
+            Self {
+                calendar_id: calendar_id.to_string(),
+            }
+        }
+
+        pub fn into_uri(self) -> String {
+            format!("/{}", encode_id(&self.calendar_id))
+        }
+    }
+
+    mod get {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/calendars/get
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) parameters: Parameters,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn get(&self, calendar_id: &str) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!(any [calendar, calendar.readonly] in &self.token().scope)?;
+                let parameters = Parameters::new(calendar_id);
+                Ok(Request {
+                    client: *self,
+                    parameters,
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self { client, parameters } = self;
+                client.request(http::Method::GET, &parameters.into_uri())
+            }
+
+            pub async fn send(self) -> Result<Calendar, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+
+    mod insert {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/calendars/insert
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) body: Calendar,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn insert(
+                &self,
+                calendar: Calendar,
+            ) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!([calendar] in &self.token().scope)?;
+                Ok(Request {
+                    client: *self,
+                    body: calendar,
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self { client, body } = self;
+                client.post_json("", &body)
+            }
+
+            pub async fn send(self) -> Result<Calendar, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+
+    mod delete {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/calendars/delete
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) parameters: Parameters,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn delete(&self, calendar_id: &str) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!([calendar] in &self.token().scope)?;
+                let parameters = Parameters::new(calendar_id);
+                Ok(Request {
+                    client: *self,
+                    parameters,
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self { client, parameters } = self;
+                client.request(http::Method::DELETE, &parameters.into_uri())
+            }
+
+            pub async fn send(self) -> Result<(), Error> {
+                let client = self.client.inner;
+                check_status(client.send(self.into_request()).await?).await?;
+                Ok(())
+            }
+        }
+    }
+
+    mod clear {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/calendars/clear
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) parameters: Parameters,
+        }
+
+        impl<'a> Client<'a> {
+            /// Deletes all events of a primary calendar.
+            pub fn clear(&self, calendar_id: &str) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!([calendar] in &self.token().scope)?;
+                let parameters = Parameters::new(calendar_id);
+                Ok(Request {
+                    client: *self,
+                    parameters,
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self { client, parameters } = self;
+                let uri = format!("{}/clear", parameters.into_uri());
+                client.request(http::Method::POST, &uri)
+            }
+
+            pub async fn send(self) -> Result<(), Error> {
+                let client = self.client.inner;
+                check_status(client.send(self.into_request()).await?).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+pub use calendar_list::{
+    CalendarList, CalendarListEntry, InvalidMaxResults, ParameterMinAccessRole,
+    ParseMinAccessRoleError,
+};
+pub use calendars::Calendar;
+
+mod acl {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    pub struct Client<'a> {
+        pub(crate) inner: CalendarClient<'a>,
+    }
+
+    impl<'a> CalendarClient<'a> {
+        #[inline]
+        pub fn acl(&self) -> Client<'a> {
+            Client { inner: *self }
+        }
+    }
+
+    impl Client<'_> {
+        pub const BASE_PATH: &'static str = "/calendars";
+
+        pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.request(method, &uri)
+        }
+
+        pub(crate) fn post_json<T>(&self, uri: &str, body: &T) -> reqwest::RequestBuilder
+        where
+            T: Serialize + ?Sized,
+        {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.post_json(&uri, body)
+        }
+
+        #[inline]
+        fn token(&self) -> &Token {
+            self.inner.token()
+        }
+
+        fn check_scope(&self) -> Result<(), InsufficientScopeError> {
+            require_scopes!([calendar] in &self.token().scope)
+        }
+    }
+
+    /// https://developers.google.com/calendar/api/v3/reference/acl#resource
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct AclRule {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub id: Option<String>,
+        pub role: AclRole,
+        pub scope: AclScope,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub enum AclRole {
+        None,
+        FreeBusyReader,
+        Reader,
+        Writer,
+        Owner,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct AclScope {
+        #[serde(rename = "type")]
+        pub r#type: AclScopeType,
+        /// omitted for [`AclScopeType::Default`]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub value: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub enum AclScopeType {
+        Default,
+        User,
+        Group,
+        Domain,
+    }
+
+    /// https://developers.google.com/calendar/api/v3/reference/acl/list#response
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AclRules {
+        #[serde(default)]
+        pub items: Vec<AclRule>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub next_page_token: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub next_sync_token: Option<String>,
+    }
+
+    mod list {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/acl/list
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) calendar_id: String,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn list(&self, calendar_id: &str) -> Result<Request<'a>, InsufficientScopeError> {
+                self.check_scope()?;
+                Ok(Request {
+                    client: *self,
+                    calendar_id: calendar_id.to_string(),
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self {
+                    client,
+                    calendar_id,
+                } = self;
+                let uri = format!("/{}/acl", encode_id(&calendar_id));
+                client.request(http::Method::GET, &uri)
+            }
+
+            pub async fn send(self) -> Result<AclRules, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+
+    mod insert {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/acl/insert
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) calendar_id: String,
+            pub(crate) body: AclRule,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn insert(
+                &self,
+                calendar_id: &str,
+                rule: AclRule,
+            ) -> Result<Request<'a>, InsufficientScopeError> {
+                self.check_scope()?;
+                Ok(Request {
+                    client: *self,
+                    calendar_id: calendar_id.to_string(),
+                    body: rule,
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self {
+                    client,
+                    calendar_id,
+                    body,
+                } = self;
+                let uri = format!("/{}/acl", encode_id(&calendar_id));
+                client.post_json(&uri, &body)
+            }
+
+            pub async fn send(self) -> Result<AclRule, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+
+    mod delete {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/acl/delete
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) calendar_id: String,
+            pub(crate) rule_id: String,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn delete(
+                &self,
+                calendar_id: &str,
+                rule_id: &str,
+            ) -> Result<Request<'a>, InsufficientScopeError> {
+                self.check_scope()?;
+                Ok(Request {
+                    client: *self,
+                    calendar_id: calendar_id.to_string(),
+                    rule_id: rule_id.to_string(),
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self {
+                    client,
+                    calendar_id,
+                    rule_id,
+                } = self;
+                let uri = format!("/{}/acl/{}", encode_id(&calendar_id), encode_id(&rule_id));
+                client.request(http::Method::DELETE, &uri)
+            }
+
+            pub async fn send(self) -> Result<(), Error> {
+                let client = self.client.inner;
+                check_status(client.send(self.into_request()).await?).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+pub use acl::{AclRole, AclRule, AclRules, AclScope, AclScopeType};
+
+mod colors {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    pub struct Client<'a> {
+        pub(crate) inner: CalendarClient<'a>,
+    }
+
+    impl<'a> CalendarClient<'a> {
+        #[inline]
+        pub fn colors(&self) -> Client<'a> {
+            Client { inner: *self }
+        }
+    }
+
+    impl Client<'_> {
+        pub const BASE_PATH: &'static str = "/colors";
+
+        pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.request(method, &uri)
+        }
+
+        #[inline]
+        fn token(&self) -> &Token {
+            self.inner.token()
+        }
+    }
+
+    /// https://developers.google.com/calendar/api/v3/reference/colors#resource
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Colors {
+        pub updated: String,
+        #[serde(default)]
+        pub calendar: HashMap<String, ColorDefinition>,
+        #[serde(default)]
+        pub event: HashMap<String, ColorDefinition>,
+    }
+
+    #[cfg(feature = "chrono")]
+    impl Colors {
+        /// Parses `updated` as an RFC3339 timestamp.
+        pub fn updated_chrono(&self) -> Result<chrono::DateTime<chrono::Utc>, chrono::ParseError> {
+            chrono::DateTime::parse_from_rfc3339(&self.updated).map(|updated| updated.to_utc())
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct ColorDefinition {
+        pub background: String,
+        pub foreground: String,
+    }
+
+    mod get {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/colors/get
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn get(&self) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!(any [calendar, calendar.readonly] in &self.token().scope)?;
+                Ok(Request { client: *self })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                self.client.request(http::Method::GET, "")
+            }
+
+            pub async fn send(self) -> Result<Colors, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+}
+
+pub use colors::{ColorDefinition, Colors};
+
+mod settings {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    pub struct Client<'a> {
+        pub(crate) inner: CalendarClient<'a>,
+    }
+
+    impl<'a> CalendarClient<'a> {
+        #[inline]
+        pub fn settings(&self) -> Client<'a> {
+            Client { inner: *self }
+        }
+    }
+
+    impl Client<'_> {
+        pub const BASE_PATH: &'static str = "/users/me/settings";
+
+        pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.request(method, &uri)
+        }
+
+        #[inline]
+        fn token(&self) -> &Token {
+            self.inner.token()
+        }
+
+        fn check_scope(&self) -> Result<(), InsufficientScopeError> {
+            require_scopes!(any [
+                calendar,
+                calendar.readonly,
+                calendar.settings.readonly
+            ] in &self.token().scope)
+        }
+    }
+
+    /// https://developers.google.com/calendar/api/v3/reference/settings#resource
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct Setting {
+        pub id: String,
+        pub value: String,
+    }
+
+    /// https://developers.google.com/calendar/api/v3/reference/settings/list#response
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SettingsList {
+        #[serde(default)]
+        pub items: Vec<Setting>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub next_page_token: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub next_sync_token: Option<String>,
+    }
+
+    mod list {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/settings/list
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn list(&self) -> Result<Request<'a>, InsufficientScopeError> {
+                self.check_scope()?;
+                Ok(Request { client: *self })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                self.client.request(http::Method::GET, "")
+            }
+
+            pub async fn send(self) -> Result<SettingsList, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+
+    mod get {
+        use super::*;
+
+        /// https://developers.google.com/calendar/api/v3/reference/settings/get
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) setting_id: String,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn get(&self, setting_id: &str) -> Result<Request<'a>, InsufficientScopeError> {
+                self.check_scope()?;
+                Ok(Request {
+                    client: *self,
+                    setting_id: setting_id.to_string(),
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self { client, setting_id } = self;
+                let uri = format!("/{}", encode_id(&setting_id));
+                client.request(http::Method::GET, &uri)
+            }
+
+            pub async fn send(self) -> Result<Setting, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub use events::EventTime;
+pub use events::{
+    Attendee, ConferenceData, EntryPoint, Event, EventDateTime, ParseResponseStatusError,
+    ReminderOverride, Reminders, ResponseStatus,
+};
+pub use settings::{Setting, SettingsList};
+
+mod batch {
+    use std::fmt::Write;
+
+    use super::*;
+
+    /// Combines several calendar calls into one `multipart/mixed` request.
+    /// https://developers.google.com/calendar/api/guides/batch
+    #[derive(Clone)]
+    pub struct Request<'a> {
+        pub(crate) client: CalendarClient<'a>,
+        pub(crate) boundary: String,
+        pub(crate) parts: Vec<Part>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct Part {
+        method: http::Method,
+        uri: String,
+        body: Option<serde_json::Value>,
+    }
+
+    /// The outcome of one operation in a batch.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Response {
+        pub status: http::StatusCode,
+        pub body: String,
+    }
+
+    impl<'a> CalendarClient<'a> {
+        /// Starts an empty batch. Scopes are checked by the server per operation.
+        pub fn batch(&self) -> Request<'a> {
+            use rand::distributions::{Alphanumeric, DistString};
+
+            let boundary = Alphanumeric.sample_string(&mut rand::thread_rng(), 24);
+            Request {
+                client: *self,
+                boundary: format!("batch_{boundary}"),
+                parts: Vec::new(),
+            }
+        }
+    }
+
+    impl Request<'_> {
+        pub const PATH: &'static str = "/batch/calendar/v3";
+
+        /// Adds an operation; `uri` is relative to [`CalendarClient::BASE_PATH`].
+        pub fn add(self, method: http::Method, uri: &str) -> Self {
+            self.push(method, uri, None)
+        }
+
+        /// Adds an operation with a JSON body.
+        pub fn add_json(self, method: http::Method, uri: &str, body: serde_json::Value) -> Self {
+            self.push(method, uri, Some(body))
+        }
+
+        fn push(self, method: http::Method, uri: &str, body: Option<serde_json::Value>) -> Self {
+            let Self {
+                client,
+                boundary,
+                mut parts,
+            } = self;
+            parts.push(Part {
+                method,
+                uri: format!("{}{}", CalendarClient::BASE_PATH, uri),
+                body,
+            });
+            Self {
+                client,
+                boundary,
+                parts,
+            }
+        }
+
+        pub(crate) fn body(&self) -> String {
+            let mut body = String::new();
+            for (
+                i,
+                Part {
+                    method,
+                    uri,
+                    body: json,
+                },
+            ) in self.parts.iter().enumerate()
+            {
+                let _ = write!(
+                    body,
+                    "--{}\r\nContent-Type: application/http\r\nContent-ID: <item{}>\r\n\r\n{method} {uri} HTTP/1.1\r\n",
+                    self.boundary,
+                    i + 1
+                );
+                match json {
+                    Some(json) => {
+                        let _ = write!(body, "Content-Type: application/json\r\n\r\n{json}\r\n");
+                    }
+                    None => body.push_str("\r\n"),
+                }
+            }
+            let _ = write!(body, "--{}--\r\n", self.boundary);
+            body
+        }
+
+        pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+            let body = self.body();
+            let content_type = format!("multipart/mixed; boundary={}", self.boundary);
+            self.client
+                .inner
+                .request(http::Method::POST, Self::PATH)
+                .header(http::header::CONTENT_TYPE, content_type)
+                .body(body)
+        }
+
+        /// Sends the batch, returning one [`Response`] per operation in the order they were added.
+        pub async fn send(self) -> Result<Vec<Response>, Error> {
+            let client = self.client;
+            let response = check_status(client.send(self.into_request()).await?).await?;
+            let boundary = response
+                .headers()
+                .get(http::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(boundary_of)
+                .ok_or_else(|| Error::Batch("missing multipart boundary".to_string()))?;
+            let body = response.text().await?;
+            parse_body(&boundary, &body)
+        }
+    }
+
+    impl Response {
+        /// Deserializes the body of a successful operation,
+        /// or returns the error it failed with as [`super::read_json`] does.
+        pub fn json<T: DeserializeOwned>(&self) -> Result<T, Error> {
+            let Self { status, body } = self;
+            if !status.is_success() {
+                let e = match GoogleApiError::from_body(body) {
+                    Some(e) => e.into(),
+                    None => Error::Status {
+                        status: *status,
+                        body: body.clone(),
+                    },
+                };
+                return Err(e);
+            }
+            Ok(serde_json::from_str(body)?)
+        }
+    }
+
+    fn boundary_of(content_type: &str) -> Option<String> {
+        content_type.split(';').find_map(|param| {
+            let (key, value) = param.trim().split_once('=')?;
+            key.eq_ignore_ascii_case("boundary")
+                .then(|| value.trim_matches('"').to_string())
+        })
+    }
+
+    /// Splits `head\n\nrest`, accepting both CRLF and LF line breaks.
+    fn split_head(s: &str) -> Option<(&str, &str)> {
+        let crlf = s.find("\r\n\r\n").map(|i| (i, 4));
+        let lf = s.find("\n\n").map(|i| (i, 2));
+        let (i, len) = match (crlf, lf) {
+            (Some(a), Some(b)) => a.min(b),
+            (a, b) => a.or(b)?,
+        };
+        Some((&s[..i], &s[i + len..]))
+    }
+
+    /// `<response-item3>` -> 3
+    fn content_index(head: &str) -> Option<usize> {
+        head.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if !key.trim().eq_ignore_ascii_case("content-id") {
+                return None;
+            }
+            let value = value.trim().trim_start_matches('<').trim_end_matches('>');
+            value.rsplit_once("item")?.1.parse().ok()
+        })
+    }
+
+    pub(crate) fn parse_body(boundary: &str, body: &str) -> Result<Vec<Response>, Error> {
+        let error = |message: &str| Error::Batch(message.to_string());
+        let delimiter = format!("--{boundary}");
+        let mut responses = Vec::new();
+        // the first chunk is the preamble, and the one after the close delimiter starts with "--"
+        for part in body.split(delimiter.as_str()).skip(1) {
+            if part.starts_with("--") {
+                break;
+            }
+            let part = part.trim_start_matches(['\r', '\n']);
+            let (part_head, http) = split_head(part).ok_or_else(|| error("no part headers"))?;
+            let (http_head, body) = split_head(http).unwrap_or((http.trim_end(), ""));
+            let status = http_head
+                .lines()
+                .next()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .and_then(|code| code.parse::<http::StatusCode>().ok())
+                .ok_or_else(|| error("invalid status line"))?;
+            let response = Response {
+                status,
+                body: body.trim_end_matches(['\r', '\n']).to_string(),
+            };
+            responses.push((content_index(part_head), response));
+        }
+        if responses.iter().all(|(index, _)| index.is_some()) {
+            responses.sort_by_key(|(index, _)| *index);
+        }
+        Ok(responses.into_iter().map(|(_, r)| r).collect())
+    }
+}
+
+pub use batch::Response as BatchResponse;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::{CalendarEvents, CalendarReadonly, CalendarSettingsReadonly};
+    use crate::test_util::authorized;
+
+    #[test]
+    fn test_calendars_requests() {
+        let client = authorized(&crate::scope::Calendar);
+        let calendars = client.calendar().calendars();
+        let cases = [
+            (
+                calendars.get("team@example.com").unwrap().into_request(),
+                http::Method::GET,
+                "/calendar/v3/calendars/team%40example.com",
+            ),
+            (
+                calendars
+                    .insert(Calendar::default())
+                    .unwrap()
+                    .into_request(),
+                http::Method::POST,
+                "/calendar/v3/calendars",
+            ),
+            (
+                calendars.delete("secondary").unwrap().into_request(),
+                http::Method::DELETE,
+                "/calendar/v3/calendars/secondary",
+            ),
+            (
+                calendars.clear("primary").unwrap().into_request(),
+                http::Method::POST,
+                "/calendar/v3/calendars/primary/clear",
+            ),
+        ];
+        for (request, method, path) in cases {
+            let request = request.build().unwrap();
+            assert_eq!(request.method(), method);
+            assert_eq!(request.url().path(), path);
+        }
+
+        let client = authorized(&CalendarReadonly);
+        let calendars = client.calendar().calendars();
+        assert!(calendars.get("primary").is_ok());
+        assert!(calendars.delete("primary").is_err());
+    }
+
+    #[test]
+    fn test_calendar_de() {
+        let payload = r#"{
+            "kind": "calendar#calendar",
+            "etag": "\"etag\"",
+            "id": "team@example.com",
+            "summary": "Team",
+            "description": "Team events",
+            "timeZone": "Asia/Tokyo",
+            "conferenceProperties": { "allowedConferenceSolutionTypes": ["hangoutsMeet"] }
+        }"#;
+        let calendar: Calendar = serde_json::from_str(payload).unwrap();
+        assert_eq!(calendar.id.as_deref(), Some("team@example.com"));
+        assert_eq!(calendar.summary, "Team");
+        assert_eq!(calendar.description.as_deref(), Some("Team events"));
+        assert_eq!(calendar.time_zone.as_deref(), Some("Asia/Tokyo"));
+        assert_eq!(calendar.location, None);
+    }
+
+    #[test]
+    fn test_acl_rules_de() {
+        let payload = r#"{
+            "kind": "calendar#acl",
+            "etag": "\"etag\"",
+            "nextSyncToken": "sync",
+            "items": [
+                {
+                    "kind": "calendar#aclRule",
+                    "id": "user:owner@example.com",
+                    "scope": { "type": "user", "value": "owner@example.com" },
+                    "role": "owner"
+                },
+                {
+                    "kind": "calendar#aclRule",
+                    "id": "default",
+                    "scope": { "type": "default" },
+                    "role": "freeBusyReader"
+                }
+            ]
+        }"#;
+        let rules: AclRules = serde_json::from_str(payload).unwrap();
+        assert_eq!(rules.next_sync_token.as_deref(), Some("sync"));
+        assert_eq!(
+            rules.items,
+            vec![
+                AclRule {
+                    id: Some("user:owner@example.com".to_string()),
+                    role: AclRole::Owner,
+                    scope: AclScope {
+                        r#type: AclScopeType::User,
+                        value: Some("owner@example.com".to_string()),
+                    },
+                },
+                AclRule {
+                    id: Some("default".to_string()),
+                    role: AclRole::FreeBusyReader,
+                    scope: AclScope {
+                        r#type: AclScopeType::Default,
+                        value: None,
+                    },
+                },
+            ]
+        );
+
+        let client = authorized(&CalendarReadonly);
+        assert!(client.calendar().acl().list("primary").is_err());
+    }
+
+    #[test]
+    fn test_colors_de() {
+        let payload = r##"{
+            "kind": "calendar#colors",
+            "updated": "2012-02-14T00:00:00.000Z",
+            "calendar": {
+                "1": { "background": "#ac725e", "foreground": "#1d1d1d" },
+                "2": { "background": "#d06b64", "foreground": "#1d1d1d" }
+            },
+            "event": {
+                "1": { "background": "#a4bdfc", "foreground": "#1d1d1d" }
+            }
+        }"##;
+        let colors: Colors = serde_json::from_str(payload).unwrap();
+        assert_eq!(colors.updated, "2012-02-14T00:00:00.000Z");
+        #[cfg(feature = "chrono")]
+        assert_eq!(colors.updated_chrono().unwrap().timestamp(), 1329177600);
+        assert_eq!(colors.calendar.len(), 2);
+        assert_eq!(
+            colors.event["1"],
+            ColorDefinition {
+                background: "#a4bdfc".to_string(),
+                foreground: "#1d1d1d".to_string(),
+            }
+        );
+
+        let client = authorized(&CalendarReadonly);
+        let request = client.calendar().colors().get().unwrap();
+        let request = request.into_request().build().unwrap();
+        assert_eq!(request.url().path(), "/calendar/v3/colors");
+    }
+
+    #[test]
+    fn test_settings() {
+        let client = authorized(&CalendarSettingsReadonly);
+        let settings = client.calendar().settings();
+        let request = settings.get("timezone").unwrap().into_request();
+        let request = request.build().unwrap();
+        assert_eq!(
+            request.url().path(),
+            "/calendar/v3/users/me/settings/timezone"
+        );
+        assert!(settings.list().is_ok());
+
+        let client = authorized(&CalendarEvents);
+        assert!(client.calendar().settings().list().is_err());
+
+        let payload = r#"{
+            "kind": "calendar#settings",
+            "etag": "\"etag\"",
+            "nextSyncToken": "sync",
+            "items": [
+                { "kind": "calendar#setting", "etag": "\"etag\"", "id": "timezone", "value": "Asia/Tokyo" },
+                { "kind": "calendar#setting", "etag": "\"etag\"", "id": "weekStart", "value": "1" }
+            ]
+        }"#;
+        let list: SettingsList = serde_json::from_str(payload).unwrap();
+        assert_eq!(
+            list.items,
+            vec![
+                Setting {
+                    id: "timezone".to_string(),
+                    value: "Asia/Tokyo".to_string(),
+                },
+                Setting {
+                    id: "weekStart".to_string(),
+                    value: "1".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calendar_list_query() {
+        let client = authorized(&crate::scope::Calendar);
+        let request = client.calendar().calendar_list().list().unwrap();
+        assert_eq!(request.parameters.clone().into_query(), "");
+
+        let request = request
+            .param_max_results(50)
+            .unwrap()
+            .param_min_access_role(ParameterMinAccessRole::Writer)
+            .param_page_token("page/2")
+            .param_show_deleted(true)
+            .param_show_hidden(false)
+            .param_sync_token("sync=");
+        assert_eq!(
+            request.parameters.into_query(),
+            "maxResults=50&minAccessRole=writer&pageToken=page%2F2\
+             &showDeleted=true&showHidden=false&syncToken=sync%3D"
+        );
+    }
+
+    #[test]
+    fn test_insufficient_scope_error() {
+        let client = authorized(&crate::scope::UserinfoEmail);
+        let Err(err) = client.calendar().calendar_list().list() else {
+            panic!("calendarList.list was allowed without a calendar scope");
+        };
+        assert_eq!(
+            err.required,
+            [crate::scope::Calendar::STR, CalendarReadonly::STR]
+        );
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "insufficient scope: need one of [{}, {}], have [{}]",
+                crate::scope::Calendar::STR,
+                CalendarReadonly::STR,
+                crate::scope::UserinfoEmail::STR
+            )
+        );
+
+        let Err(err) = client.calendar().events().delete("primary", "event1") else {
+            panic!("events.delete was allowed without a calendar scope");
+        };
+        assert!(
+            err.to_string()
+                .starts_with("insufficient scope: need one of ["),
+            "{err}"
+        );
+
+        let client = authorized(&CalendarReadonly);
+        assert!(client.calendar().calendar_list().list().is_ok());
+        assert!(client.calendar().calendar_list().get("primary").is_ok());
+    }
+
+    #[test]
+    fn test_calendar_list_max_results() {
+        let client = authorized(&crate::scope::Calendar);
+        let request = client.calendar().calendar_list().list().unwrap();
+        let request = request.param_max_results(250).unwrap();
+        assert_eq!(request.parameters.clone().into_query(), "maxResults=250");
+        let err = request.clone().param_max_results(251).err().unwrap();
+        assert_eq!(err, InvalidMaxResults(251));
+        let err = request.param_max_results(0).err().unwrap();
+        assert_eq!(err, InvalidMaxResults(0));
+    }
+
+    #[tokio::test]
+    async fn test_calendar_list_send_raw() {
+        let router = axum::Router::new().route(
+            "/calendar/v3/users/me/calendarList",
+            axum::routing::get(|| async {
+                let headers = [("x-ratelimit-remaining", "0")];
+                (http::StatusCode::TOO_MANY_REQUESTS, headers, "rate limited")
+            }),
+        );
+        let addr = crate::test_util::serve(router).await;
+        let client = authorized(&crate::scope::Calendar).with_base_url(format!("http://{addr}"));
+        let request = client.calendar().calendar_list().list().unwrap();
+        let response = request.send_raw().await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["x-ratelimit-remaining"], "0");
+        assert_eq!(response.text().await.unwrap(), "rate limited");
+    }
+
+    async fn serve_calendar_list() -> std::net::SocketAddr {
+        use std::collections::HashMap;
+
+        use axum::extract::Query;
+        use axum::response::IntoResponse;
+
+        let handler = |Query(query): Query<HashMap<String, String>>| async move {
+            let page_token = query.get("pageToken").map(String::as_str);
+            let body = match (query.get("syncToken").map(String::as_str), page_token) {
+                (Some("expired"), _) => {
+                    return (http::StatusCode::GONE, "fullSyncRequired").into_response()
+                }
+                (Some("sync1"), None) => serde_json::json!({
+                    "items": [{ "id": "changed" }],
+                    "nextSyncToken": "sync2"
+                }),
+                (None, None) => serde_json::json!({
+                    "items": [{ "id": "primary" }],
+                    "nextPageToken": "page2"
+                }),
+                (None, Some("page2")) => serde_json::json!({
+                    "items": [{ "id": "team" }],
+                    "nextSyncToken": "sync1"
+                }),
+                _ => return http::StatusCode::BAD_REQUEST.into_response(),
+            };
+            axum::Json(body).into_response()
+        };
+        let router = axum::Router::new().route(
+            "/calendar/v3/users/me/calendarList",
+            axum::routing::get(handler),
+        );
+        crate::test_util::serve(router).await
+    }
+
+    #[tokio::test]
+    async fn test_calendar_list_sync() {
+        let addr = serve_calendar_list().await;
+        let client = authorized(&crate::scope::Calendar).with_base_url(format!("http://{addr}"));
+        let calendar_list = client.calendar().calendar_list();
+
+        let (entries, sync_token) = calendar_list.sync(None).await.unwrap();
+        let ids: Vec<_> = entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["primary", "team"]);
+        assert_eq!(sync_token, "sync1");
+
+        let (entries, sync_token) = calendar_list.sync(Some(sync_token)).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, "changed");
+        assert_eq!(sync_token, "sync2");
+    }
+
+    #[tokio::test]
+    async fn test_calendar_list_sync_expired() {
+        let addr = serve_calendar_list().await;
+        let client = authorized(&crate::scope::Calendar).with_base_url(format!("http://{addr}"));
+        let calendar_list = client.calendar().calendar_list();
+
+        let err = calendar_list
+            .sync(Some("expired".to_string()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::SyncTokenExpired), "{err:?}");
+        let (entries, _) = calendar_list.sync(None).await.unwrap();
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn test_batch() {
+        let client = authorized(&crate::scope::Calendar);
+        let batch = client
+            .calendar()
+            .batch()
+            .add(http::Method::GET, "/users/me/calendarList")
+            .add_json(
+                http::Method::PATCH,
+                "/calendars/primary",
+                serde_json::json!({"summary": "renamed"}),
+            );
+        let boundary = batch.boundary.clone();
+        let request = batch.into_request().build().unwrap();
+        assert_eq!(request.method(), http::Method::POST);
+        assert_eq!(request.url().path(), "/batch/calendar/v3");
+        assert_eq!(
+            request.headers()[http::header::CONTENT_TYPE],
+            format!("multipart/mixed; boundary={boundary}")
+        );
+        let body = request.body().unwrap().as_bytes().unwrap();
+        let expected = format!(
+            "--{boundary}\r\nContent-Type: application/http\r\nContent-ID: <item1>\r\n\r\n\
+             GET /calendar/v3/users/me/calendarList HTTP/1.1\r\n\r\n\
+             --{boundary}\r\nContent-Type: application/http\r\nContent-ID: <item2>\r\n\r\n\
+             PATCH /calendar/v3/calendars/primary HTTP/1.1\r\n\
+             Content-Type: application/json\r\n\r\n{{\"summary\":\"renamed\"}}\r\n\
+             --{boundary}--\r\n"
+        );
+        assert_eq!(std::str::from_utf8(body).unwrap(), expected);
+
+        // parts may come back out of order
+        let response = "--batch_abc\r\n\
+             Content-Type: application/http\r\n\
+             Content-ID: <response-item2>\r\n\r\n\
+             HTTP/1.1 404 Not Found\r\n\
+             Content-Type: application/json; charset=UTF-8\r\n\r\n\
+             {\"error\":{\"code\":404,\"message\":\"Not Found\"}}\r\n\
+             --batch_abc\r\n\
+             Content-Type: application/http\r\n\
+             Content-ID: <response-item1>\r\n\r\n\
+             HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json; charset=UTF-8\r\n\r\n\
+             {\"items\":[{\"id\":\"primary\"}]}\r\n\
+             --batch_abc--\r\n";
+        let responses = batch::parse_body("batch_abc", response).unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].status, http::StatusCode::OK);
+        let list: CalendarList = responses[0].json().unwrap();
+        assert_eq!(list.items[0].id, "primary");
+        assert_eq!(responses[1].status, http::StatusCode::NOT_FOUND);
+        let err = responses[1].json::<Calendar>().unwrap_err();
+        assert!(
+            matches!(err, Error::Api(GoogleApiError { code: 404, .. })),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_google_api_error() {
+        let router = axum::Router::new().route(
+            "/calendar/v3/users/me/calendarList",
+            axum::routing::get(|| async {
+                let body = serde_json::json!({
+                    "error": {
+                        "code": 403,
+                        "message": "The request is missing a valid API key.",
+                        "errors": [{
+                            "domain": "global",
+                            "reason": "forbidden",
+                            "message": "The request is missing a valid API key."
+                        }],
+                        "status": "PERMISSION_DENIED"
+                    }
+                });
+                (http::StatusCode::FORBIDDEN, axum::Json(body))
+            }),
+        );
+        let addr = crate::test_util::serve(router).await;
+        let client = authorized(&crate::scope::Calendar).with_base_url(format!("http://{addr}"));
+        let request = client.calendar().calendar_list().list().unwrap();
+        let Err(Error::Api(e)) = request.send().await else {
+            panic!("expected GoogleApiError");
+        };
+        assert_eq!(e.code, 403);
+        assert_eq!(e.message, "The request is missing a valid API key.");
+        assert_eq!(e.errors[0].reason.as_deref(), Some("forbidden"));
+        assert_eq!(e.status.as_deref(), Some("PERMISSION_DENIED"));
+    }
+
+    #[test]
+    fn test_min_access_role_strings() {
+        for role in ParameterMinAccessRole::ALL {
+            let json = serde_json::to_value(role).unwrap();
+            assert_eq!(json, serde_json::Value::from(role.to_string()));
+            assert_eq!(role.as_str(), role.to_string());
+            assert_eq!(role.as_str().parse(), Ok(*role));
+            assert_eq!(
+                serde_json::from_value::<ParameterMinAccessRole>(json).unwrap(),
+                *role
+            );
+        }
+        let err = "admin".parse::<ParameterMinAccessRole>().unwrap_err();
+        assert!(
+            err.to_string()
+                .ends_with("freeBusyReader, owner, reader, writer"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_min_access_role_parse() {
+        assert_eq!(
+            "freeBusyReader".parse(),
+            Ok(ParameterMinAccessRole::FreeBusyReader)
+        );
+        let err = "admin".parse::<ParameterMinAccessRole>().unwrap_err();
+        assert_eq!(err, ParseMinAccessRoleError("admin".to_string()));
+        assert!(err.to_string().contains("\"admin\""), "{err}");
+    }
+
+    #[test]
+    fn test_events_get() {
+        let client = authorized(&CalendarReadonly);
+        let request = client
+            .calendar()
+            .events()
+            .get("primary", "event#1")
+            .unwrap()
+            .into_request()
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), http::Method::GET);
+        assert_eq!(
+            request.url().path(),
+            "/calendar/v3/calendars/primary/events/event%231"
+        );
+    }
+
+    #[test]
+    fn test_events_delete() {
+        let client = authorized(&CalendarEvents);
+        let request = client
+            .calendar()
+            .events()
+            .delete("primary", "event1")
+            .unwrap()
+            .into_request()
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), http::Method::DELETE);
+        assert_eq!(
+            request.url().path(),
+            "/calendar/v3/calendars/primary/events/event1"
+        );
+
+        let client = authorized(&CalendarReadonly);
+        assert!(client
+            .calendar()
+            .events()
+            .delete("primary", "event1")
+            .is_err());
+    }
+
+    #[test]
+    fn test_events_patch() {
+        let client = authorized(&CalendarEvents);
+        let partial = Event {
+            summary: Some("Meeting".to_string()),
+            ..Event::default()
+        };
+        let request = client
+            .calendar()
+            .events()
+            .patch("primary", "event1", partial)
+            .unwrap()
+            .into_request()
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), http::Method::PATCH);
+        assert_eq!(
+            request.url().path(),
+            "/calendar/v3/calendars/primary/events/event1"
+        );
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+        let body: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(body, serde_json::json!({ "summary": "Meeting" }));
+    }
+
+    #[test]
+    fn test_event_de() {
+        let payload = r#"{
+            "kind": "calendar#event",
+            "id": "event1",
+            "status": "confirmed",
+            "summary": "Weekly sync",
+            "recurrence": ["RRULE:FREQ=WEEKLY;BYDAY=MO"],
+            "attendees": [
+                {
+                    "email": "alice@example.com",
+                    "organizer": true,
+                    "self": true,
+                    "responseStatus": "accepted"
+                },
+                {
+                    "email": "bob@example.com",
+                    "optional": true,
+                    "responseStatus": "needsAction"
+                }
+            ],
+            "conferenceData": {
+                "conferenceId": "abc-defg-hij",
+                "entryPoints": [
+                    { "entryPointType": "video", "uri": "https://meet.google.com/abc-defg-hij" }
+                ]
+            },
+            "reminders": {
+                "useDefault": false,
+                "overrides": [{ "method": "popup", "minutes": 10 }]
+            }
+        }"#;
+        let event: Event = serde_json::from_str(payload).unwrap();
+        assert_eq!(event.status.as_deref(), Some("confirmed"));
+        assert_eq!(event.recurrence, ["RRULE:FREQ=WEEKLY;BYDAY=MO"]);
+        assert_eq!(event.attendees.len(), 2);
+        let alice = &event.attendees[0];
+        assert!(alice.organizer && alice.is_self && !alice.optional);
+        assert_eq!(alice.response_status, Some(ResponseStatus::Accepted));
+        let bob = &event.attendees[1];
+        assert!(bob.optional);
+        assert_eq!(bob.response_status, Some(ResponseStatus::NeedsAction));
+        let conference = event.conference_data.as_ref().unwrap();
+        assert_eq!(conference.entry_points[0].entry_point_type, "video");
+        let reminders = event.reminders.as_ref().unwrap();
+        assert!(!reminders.use_default);
+        assert_eq!(reminders.overrides[0].minutes, 10);
+
+        let json = serde_json::to_value(&event.attendees[1]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "email": "bob@example.com",
+                "responseStatus": "needsAction",
+                "optional": true
+            })
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_event_date_time_chrono() {
+        use chrono::{NaiveDate, TimeZone, Utc};
+
+        let event: Event = serde_json::from_value(serde_json::json!({
+            "start": { "date": "2024-03-20" },
+            "end": { "dateTime": "2024-03-21T09:30:00+09:00", "timeZone": "Asia/Tokyo" }
+        }))
+        .unwrap();
+        let start = event.start.unwrap();
+        let end = event.end.unwrap();
+        assert_eq!(start.date.as_deref(), Some("2024-03-20"));
+        assert_eq!(
+            start.to_chrono(),
+            Ok(Some(EventTime::AllDay(
+                NaiveDate::from_ymd_opt(2024, 3, 20).unwrap()
+            )))
+        );
+        let expected = Utc.with_ymd_and_hms(2024, 3, 21, 0, 30, 0).unwrap();
+        assert_eq!(end.to_chrono(), Ok(Some(EventTime::At(expected))));
+        assert_eq!(end.time_zone.as_deref(), Some("Asia/Tokyo"));
+        assert_eq!(EventDateTime::default().to_chrono(), Ok(None));
+        let malformed = EventDateTime {
+            date: Some("tomorrow".to_string()),
+            ..Default::default()
+        };
+        assert!(malformed.to_chrono().is_err());
+    }
+
+    #[test]
+    fn test_events_quick_add() {
+        let client = authorized(&CalendarEvents);
+        let request = client
+            .calendar()
+            .events()
+            .quick_add("primary", "Lunch with Ann tomorrow 12pm")
+            .unwrap()
+            .into_request()
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), http::Method::POST);
+        assert_eq!(
+            request.url().path(),
+            "/calendar/v3/calendars/primary/events/quickAdd"
+        );
+        assert_eq!(
+            request.url().query(),
+            Some("text=Lunch%20with%20Ann%20tomorrow%2012pm")
+        );
+
+        let client = authorized(&CalendarReadonly);
+        assert!(client
+            .calendar()
+            .events()
+            .quick_add("primary", "Lunch")
+            .is_err());
     }
 }
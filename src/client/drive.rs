@@ -0,0 +1,422 @@
+use crate::error::Error;
+use crate::require_scopes;
+
+use super::api::{encode_query, read_json};
+use super::{AuthorizedClient, InsufficientScopeError, Token};
+
+#[derive(Clone, Copy)]
+pub struct DriveClient<'a> {
+    inner: &'a AuthorizedClient,
+}
+
+impl AuthorizedClient {
+    #[inline]
+    pub fn drive(&self) -> DriveClient<'_> {
+        DriveClient { inner: self }
+    }
+}
+
+impl DriveClient<'_> {
+    pub const BASE_PATH: &'static str = "/drive/v3";
+
+    pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+        let uri = format!("{}{}", Self::BASE_PATH, uri);
+        self.inner.request(method, &uri)
+    }
+
+    #[inline]
+    pub(crate) async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, Error> {
+        self.inner.send(request).await
+    }
+
+    #[inline]
+    fn token(&self) -> &Token {
+        &self.inner.token
+    }
+}
+
+mod files {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::client::pagination::{Page, Paginated};
+
+    pub use list::{InvalidPageSize, Parameters as ListParameters, Response as FileList};
+
+    #[derive(Clone, Copy)]
+    pub struct Client<'a> {
+        pub(crate) inner: DriveClient<'a>,
+    }
+
+    impl<'a> DriveClient<'a> {
+        #[inline]
+        pub fn files(&self) -> Client<'a> {
+            Client { inner: *self }
+        }
+    }
+
+    impl Client<'_> {
+        pub const BASE_PATH: &'static str = "/files";
+
+        pub(crate) fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+            let uri = format!("{}{}", Self::BASE_PATH, uri);
+            self.inner.request(method, &uri)
+        }
+
+        #[inline]
+        fn token(&self) -> &Token {
+            self.inner.token()
+        }
+    }
+
+    /// https://developers.google.com/drive/api/reference/rest/v3/files#resource:-file
+    ///
+    /// Only the fields returned by default and a few common ones;
+    /// request others with [`list::Request::param_fields`].
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct DriveFile {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub mime_type: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub parents: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub modified_time: Option<String>,
+        /// int64 sent as a string; absent for folders and Google Docs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub size: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub web_view_link: Option<String>,
+    }
+
+    mod list {
+        use std::borrow::Cow;
+        use std::ops::RangeInclusive;
+
+        use super::*;
+
+        /// https://developers.google.com/drive/api/reference/rest/v3/files/list
+        #[derive(Clone)]
+        pub struct Request<'a> {
+            pub(crate) client: Client<'a>,
+            pub(crate) parameters: Parameters,
+        }
+
+        impl<'a> Client<'a> {
+            pub fn list(&self) -> Result<Request<'a>, InsufficientScopeError> {
+                require_scopes!(any [
+                    drive,
+                    drive.readonly,
+                    drive.file,
+                    drive.metadata,
+                    drive.metadata.readonly,
+                    drive.appdata
+                ] in &self.token().scope)?;
+                Ok(Request {
+                    client: *self,
+                    parameters: Parameters::new(),
+                })
+            }
+        }
+
+        impl Request<'_> {
+            pub fn replace_parameters<F>(self, with: F) -> Self
+            where
+                F: FnOnce(Parameters) -> Parameters,
+            {
+                let Self { client, parameters } = self;
+                Self {
+                    client,
+                    parameters: with(parameters),
+                }
+            }
+
+            pub fn param_q<'s, S>(self, value: S) -> Self
+            where
+                S: Into<Cow<'s, str>>,
+            {
+                self.replace_parameters(|p| p.q(value))
+            }
+
+            pub fn param_page_size(self, value: u16) -> Result<Self, InvalidPageSize> {
+                let Self { client, parameters } = self;
+                Ok(Self {
+                    client,
+                    parameters: parameters.page_size(value)?,
+                })
+            }
+
+            pub fn param_page_token<'s, S>(self, value: S) -> Self
+            where
+                S: Into<Cow<'s, str>>,
+            {
+                self.replace_parameters(|p| p.page_token(value))
+            }
+
+            pub fn param_fields<'s, S>(self, value: S) -> Self
+            where
+                S: Into<Cow<'s, str>>,
+            {
+                self.replace_parameters(|p| p.fields(value))
+            }
+
+            pub fn param_order_by<'s, S>(self, value: S) -> Self
+            where
+                S: Into<Cow<'s, str>>,
+            {
+                self.replace_parameters(|p| p.order_by(value))
+            }
+
+            pub(crate) fn into_request(self) -> reqwest::RequestBuilder {
+                let Self { client, parameters } = self;
+                let query = parameters.into_query();
+                let uri = if query.is_empty() {
+                    String::new()
+                } else {
+                    format!("?{query}")
+                };
+                client.request(http::Method::GET, &uri)
+            }
+
+            pub async fn send(self) -> Result<Response, Error> {
+                let client = self.client.inner;
+                read_json(client.send(self.into_request()).await?).await
+            }
+        }
+
+        #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+        pub struct Parameters {
+            q: Option<String>,
+            page_size: Option<u16>,
+            page_token: Option<String>,
+            fields: Option<String>,
+            order_by: Option<String>,
+        }
+
+        impl Parameters {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            pub const PAGE_SIZE: RangeInclusive<u16> = 1..=1000;
+
+            /// A search query such as `mimeType = 'application/pdf' and trashed = false`.
+            pub fn q<'a, S>(self, value: S) -> Self
+            where
+                S: Into<Cow<'a, str>>,
+            {
+                Self {
+                    q: Some(value.into().into_owned()),
+                    ..self
+                }
+            }
+
+            /// Fails unless `value` is within [`Self::PAGE_SIZE`].
+            pub fn page_size(self, value: u16) -> Result<Self, InvalidPageSize> {
+                if !Self::PAGE_SIZE.contains(&value) {
+                    return Err(InvalidPageSize(value));
+                }
+                Ok(Self {
+                    page_size: Some(value),
+                    ..self
+                })
+            }
+
+            pub fn page_token<'a, S>(self, value: S) -> Self
+            where
+                S: Into<Cow<'a, str>>,
+            {
+                Self {
+                    page_token: Some(value.into().into_owned()),
+                    ..self
+                }
+            }
+
+            /// Partial response selector, e.g. `nextPageToken, files(id, name, size)`.
+            pub fn fields<'a, S>(self, value: S) -> Self
+            where
+                S: Into<Cow<'a, str>>,
+            {
+                Self {
+                    fields: Some(value.into().into_owned()),
+                    ..self
+                }
+            }
+
+            /// Comma-separated sort keys, e.g. `folder,modifiedTime desc`.
+            pub fn order_by<'a, S>(self, value: S) -> Self
+            where
+                S: Into<Cow<'a, str>>,
+            {
+                Self {
+                    order_by: Some(value.into().into_owned()),
+                    ..self
+                }
+            }
+
+            pub fn to_params(&self) -> Vec<(&'static str, String)> {
+                let Self {
+                    q,
+                    page_size,
+                    page_token,
+                    fields,
+                    order_by,
+                } = self;
+                let params = [
+                    ("q", q.clone()),
+                    ("pageSize", page_size.map(|v| v.to_string())),
+                    ("pageToken", page_token.clone()),
+                    ("fields", fields.clone()),
+                    ("orderBy", order_by.clone()),
+                ];
+                params
+                    .into_iter()
+                    .filter_map(|(key, value)| Some((key, value?)))
+                    .collect()
+            }
+
+            pub fn into_query(self) -> String {
+                encode_query(self.to_params())
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+        #[error("pageSize must be within 1..=1000, got {0}")]
+        pub struct InvalidPageSize(pub(crate) u16);
+
+        /// https://developers.google.com/drive/api/reference/rest/v3/files/list#response-body
+        #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct Response {
+            #[serde(default)]
+            pub files: Vec<DriveFile>,
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub next_page_token: Option<String>,
+            /// Whether some corpora were skipped, so more files may match
+            #[serde(default)]
+            pub incomplete_search: bool,
+        }
+
+        impl From<Response> for Page<DriveFile> {
+            fn from(value: Response) -> Self {
+                let Response {
+                    files,
+                    next_page_token,
+                    ..
+                } = value;
+                Page {
+                    items: files,
+                    next_page_token,
+                }
+            }
+        }
+
+        impl Paginated for Request<'_> {
+            type Item = DriveFile;
+            type Error = Error;
+
+            fn with_page_token(self, token: String) -> Self {
+                self.param_page_token(token)
+            }
+
+            async fn fetch_page(self) -> Result<Page<DriveFile>, Error> {
+                Ok(self.send().await?.into())
+            }
+        }
+    }
+}
+
+pub use files::{DriveFile, FileList, InvalidPageSize, ListParameters};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::{CalendarReadonly, DriveMetadataReadonly};
+    use crate::test_util::authorized;
+
+    #[test]
+    fn test_files_list_request() {
+        let client = authorized(&DriveMetadataReadonly);
+        let request = client
+            .drive()
+            .files()
+            .list()
+            .unwrap()
+            .param_q("name contains 'report' and trashed = false")
+            .param_page_size(100)
+            .unwrap()
+            .param_fields("nextPageToken, files(id, name)")
+            .param_order_by("modifiedTime desc")
+            .into_request()
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), http::Method::GET);
+        assert_eq!(request.url().path(), "/drive/v3/files");
+        let query: Vec<_> = request.url().query_pairs().into_owned().collect();
+        let expected = [
+            ("q", "name contains 'report' and trashed = false"),
+            ("pageSize", "100"),
+            ("fields", "nextPageToken, files(id, name)"),
+            ("orderBy", "modifiedTime desc"),
+        ];
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(query, expected);
+
+        let request = client.drive().files().list().unwrap();
+        assert_eq!(
+            request.param_page_size(1001).err(),
+            Some(InvalidPageSize(1001))
+        );
+        assert!(authorized(&CalendarReadonly)
+            .drive()
+            .files()
+            .list()
+            .is_err());
+    }
+
+    #[test]
+    fn test_file_list_de() {
+        let payload = r#"{
+            "kind": "drive#fileList",
+            "nextPageToken": "page2",
+            "incompleteSearch": false,
+            "files": [
+                {
+                    "kind": "drive#file",
+                    "id": "1a2b3c",
+                    "name": "report.pdf",
+                    "mimeType": "application/pdf",
+                    "parents": ["0AXyz"],
+                    "modifiedTime": "2024-01-02T03:04:05.000Z",
+                    "size": "1024"
+                },
+                {
+                    "kind": "drive#file",
+                    "id": "4d5e6f",
+                    "name": "Notes",
+                    "mimeType": "application/vnd.google-apps.document"
+                }
+            ]
+        }"#;
+        let list: FileList = serde_json::from_str(payload).unwrap();
+        assert_eq!(list.next_page_token.as_deref(), Some("page2"));
+        assert!(!list.incomplete_search);
+        assert_eq!(list.files.len(), 2);
+        let file = &list.files[0];
+        assert_eq!(file.id.as_deref(), Some("1a2b3c"));
+        assert_eq!(file.mime_type.as_deref(), Some("application/pdf"));
+        assert_eq!(file.parents, ["0AXyz"]);
+        assert_eq!(file.size.as_deref(), Some("1024"));
+        assert_eq!(list.files[1].size, None);
+    }
+}
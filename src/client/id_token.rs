@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+use super::Token;
+
+/// https://developers.google.com/identity/openid-connect/openid-connect#an-id-tokens-payload
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: u64,
+    pub iat: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+}
+
+const ISSUERS: [&str; 2] = ["https://accounts.google.com", "accounts.google.com"];
+
+impl Token {
+    /// Present when the `openid` scope was requested.
+    #[inline]
+    pub fn id_token(&self) -> Option<&str> {
+        self.id_token.as_deref()
+    }
+
+    /// Decodes [`Self::id_token`], validating `iss`, `aud`, `exp`
+    /// and, if `nonce` is given, that the `nonce` claim equals it.
+    ///
+    /// The signature is not verified, which is sound only for tokens received
+    /// directly from the token endpoint over TLS.
+    pub fn id_token_claims(
+        &self,
+        client_id: &str,
+        nonce: Option<&str>,
+    ) -> Result<IdTokenClaims, Error> {
+        let id_token = self.id_token().ok_or(Error::MissingIdToken)?;
+        decode_id_token(id_token, client_id, nonce)
+    }
+}
+
+pub(crate) fn decode_id_token(
+    id_token: &str,
+    client_id: &str,
+    nonce: Option<&str>,
+) -> Result<IdTokenClaims, Error> {
+    use jsonwebtoken::{DecodingKey, Validation};
+
+    let mut validation = Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.set_audience(&[client_id]);
+    validation.set_issuer(&ISSUERS);
+    let key = DecodingKey::from_secret(&[]);
+    let claims: IdTokenClaims = jsonwebtoken::decode(id_token, &key, &validation)?.claims;
+    if let Some(expected) = nonce {
+        if claims.nonce.as_deref() != Some(expected) {
+            return Err(Error::NonceMismatch);
+        }
+    }
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::unix_now;
+
+    fn id_token(nonce: &str) -> String {
+        use jsonwebtoken::{EncodingKey, Header};
+
+        let now = unix_now();
+        let claims = serde_json::json!({
+            "iss": "https://accounts.google.com",
+            "sub": "1234567890",
+            "aud": "client_id",
+            "exp": now + 3600,
+            "iat": now,
+            "nonce": nonce,
+            "email": "user@example.com"
+        });
+        let key = EncodingKey::from_secret(b"secret");
+        jsonwebtoken::encode(&Header::default(), &claims, &key).unwrap()
+    }
+
+    #[test]
+    fn test_decode_id_token() {
+        let claims =
+            decode_id_token(&id_token("n-0S6_WzA2Mj"), "client_id", Some("n-0S6_WzA2Mj")).unwrap();
+        assert_eq!(claims.sub, "1234567890");
+        assert_eq!(claims.email.as_deref(), Some("user@example.com"));
+    }
+
+    #[test]
+    fn test_decode_id_token_nonce_mismatch() {
+        let result = decode_id_token(&id_token("n-0S6_WzA2Mj"), "client_id", Some("replayed"));
+        assert!(matches!(result, Err(Error::NonceMismatch)));
+    }
+
+    #[test]
+    fn test_decode_id_token_wrong_audience() {
+        let result = decode_id_token(&id_token("nonce"), "another_client", None);
+        assert!(matches!(result, Err(Error::Jwt(_))));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::fmt;
 use std::str::FromStr;
 
@@ -51,7 +52,7 @@ impl<'de> Deserialize<'de> for AuthorizationCode {
     }
 }
 
-impl<'de> de::Visitor<'de> for AuthorizationCodeVisitor {
+impl de::Visitor<'_> for AuthorizationCodeVisitor {
     type Value = AuthorizationCode;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -115,7 +116,7 @@ impl<'de> Deserialize<'de> for RefreshToken {
     }
 }
 
-impl<'de> de::Visitor<'de> for RefreshTokenVisitor {
+impl de::Visitor<'_> for RefreshTokenVisitor {
     type Value = RefreshToken;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -138,7 +139,7 @@ struct BearerVisitor;
 impl Bearer {
     pub const STR: &'static str = "Bearer";
 
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self(())
     }
 }
@@ -152,8 +153,9 @@ impl fmt::Display for Bearer {
 impl FromStr for Bearer {
     type Err = &'static str;
 
+    /// Case-insensitive, as some servers send `bearer`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s == Self::STR {
+        if s.eq_ignore_ascii_case(Self::STR) {
             Ok(Self::new())
         } else {
             Err("not Bearer")
@@ -179,11 +181,75 @@ impl<'de> Deserialize<'de> for Bearer {
     }
 }
 
-impl<'de> de::Visitor<'de> for BearerVisitor {
+impl de::Visitor<'_> for BearerVisitor {
     type Value = Bearer;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str(r#"a str "Bearer""#)
+        formatter.write_str(r#"a str "Bearer" in any case"#)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(E::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JwtBearer(());
+
+struct JwtBearerVisitor;
+
+impl JwtBearer {
+    pub const STR: &'static str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+    pub(super) fn new() -> Self {
+        Self(())
+    }
+}
+
+impl fmt::Display for JwtBearer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(Self::STR)
+    }
+}
+
+impl FromStr for JwtBearer {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == Self::STR {
+            Ok(Self::new())
+        } else {
+            Err("not urn:ietf:params:oauth:grant-type:jwt-bearer")
+        }
+    }
+}
+
+impl Serialize for JwtBearer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(Self::STR)
+    }
+}
+
+impl<'de> Deserialize<'de> for JwtBearer {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(JwtBearerVisitor)
+    }
+}
+
+impl de::Visitor<'_> for JwtBearerVisitor {
+    type Value = JwtBearer;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(r#"a str "urn:ietf:params:oauth:grant-type:jwt-bearer""#)
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -193,3 +259,259 @@ impl<'de> de::Visitor<'de> for BearerVisitor {
         v.parse().map_err(E::custom)
     }
 }
+
+/// The `response_type` of the authorization URL.
+/// Only flows including `code` go through the token exchange.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResponseType {
+    /// The authorization code flow
+    #[default]
+    #[serde(rename = "code")]
+    Code,
+    /// The implicit flow, returning the access token in the redirect URI fragment
+    #[serde(rename = "token")]
+    Token,
+    /// Both a code and an access token, e.g. for hybrid OpenID Connect flows
+    #[serde(rename = "code token")]
+    CodeToken,
+}
+
+impl ResponseType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Code => "code",
+            Self::Token => "token",
+            Self::CodeToken => "code token",
+        }
+    }
+
+    /// Whether the authorization server returns a code to exchange for a token.
+    pub fn includes_code(&self) -> bool {
+        matches!(self, Self::Code | Self::CodeToken)
+    }
+
+    #[inline]
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for ResponseType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A value of the authorization URL's `prompt`, combined in a [`PromptSet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Prompt {
+    /// Shows no screen, failing if the user would have to interact
+    None,
+    /// Asks for consent even if already granted, e.g. to obtain a new refresh token
+    Consent,
+    /// Lets the user pick an account
+    SelectAccount,
+}
+
+impl Prompt {
+    pub const ALL: [Self; 3] = [Self::None, Self::Consent, Self::SelectAccount];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Consent => "consent",
+            Self::SelectAccount => "select_account",
+        }
+    }
+}
+
+impl fmt::Display for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Prompt {
+    type Err = PromptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.as_str() == s)
+            .ok_or_else(|| PromptError::Unknown(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum PromptError {
+    #[error("unknown prompt {0:?}, expected one of none, consent, select_account")]
+    Unknown(String),
+    #[error("prompt none cannot be combined with other values")]
+    NoneCombined,
+}
+
+/// The space-delimited `prompt` of the authorization URL.
+/// Empty by default, leaving the parameter out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PromptSet(BTreeSet<Prompt>);
+
+impl PromptSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `prompt`, failing with [`PromptError::NoneCombined`]
+    /// if it would put [`Prompt::None`] together with another value.
+    pub fn with(self, prompt: Prompt) -> Result<Self, PromptError> {
+        let Self(mut prompts) = self;
+        prompts.insert(prompt);
+        if prompts.len() > 1 && prompts.contains(&Prompt::None) {
+            return Err(PromptError::NoneCombined);
+        }
+        Ok(Self(prompts))
+    }
+
+    #[inline]
+    pub fn contains(&self, prompt: Prompt) -> bool {
+        self.0.contains(&prompt)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Prompt> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl From<Prompt> for PromptSet {
+    fn from(value: Prompt) -> Self {
+        Self(BTreeSet::from([value]))
+    }
+}
+
+impl fmt::Display for PromptSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values: Vec<_> = self.iter().map(|p| p.as_str()).collect();
+        f.write_str(&values.join(" "))
+    }
+}
+
+impl FromStr for PromptSet {
+    type Err = PromptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace()
+            .try_fold(Self::new(), |set, p| set.with(p.parse()?))
+    }
+}
+
+impl Serialize for PromptSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PromptSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let s = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Accepts both a number and a numeric string, e.g. `3600` and `"3600"`.
+/// Formats that are not self-describing only accept the number.
+pub(crate) fn deserialize_u32_lenient<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(LenientU32Visitor)
+    } else {
+        deserializer.deserialize_u32(LenientU32Visitor)
+    }
+}
+
+struct LenientU32Visitor;
+
+impl de::Visitor<'_> for LenientU32Visitor {
+    type Value = u32;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a u32 or a str representing a u32")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        u32::try_from(v).map_err(E::custom)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        u32::try_from(v).map_err(E::custom)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.trim().parse().map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bearer_case_insensitive() {
+        for s in ["Bearer", "bearer", "BEARER"] {
+            let bearer: Bearer = serde_json::from_value(serde_json::json!(s)).unwrap();
+            assert_eq!(serde_json::to_value(bearer).unwrap(), "Bearer");
+        }
+        assert!(serde_json::from_value::<Bearer>(serde_json::json!("Mac")).is_err());
+        assert!("Mac".parse::<Bearer>().is_err());
+    }
+
+    #[test]
+    fn test_prompt_set() {
+        let single = PromptSet::from(Prompt::Consent);
+        assert_eq!(single.to_string(), "consent");
+        assert_eq!("consent".parse::<PromptSet>().unwrap(), single);
+
+        let combined = PromptSet::new()
+            .with(Prompt::SelectAccount)
+            .and_then(|p| p.with(Prompt::Consent))
+            .unwrap();
+        assert_eq!(combined.to_string(), "consent select_account");
+        assert_eq!(
+            serde_json::to_value(&combined).unwrap(),
+            "consent select_account"
+        );
+        let de: PromptSet =
+            serde_json::from_value(serde_json::json!("select_account consent")).unwrap();
+        assert_eq!(de, combined);
+
+        let err = PromptSet::from(Prompt::None)
+            .with(Prompt::Consent)
+            .unwrap_err();
+        assert_eq!(err, PromptError::NoneCombined);
+        let err = "none consent".parse::<PromptSet>().unwrap_err();
+        assert_eq!(err, PromptError::NoneCombined);
+        let err = "login".parse::<PromptSet>().unwrap_err();
+        assert_eq!(err, PromptError::Unknown("login".to_string()));
+        assert!(PromptSet::from(Prompt::None).with(Prompt::None).is_ok());
+    }
+}
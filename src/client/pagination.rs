@@ -0,0 +1,97 @@
+use std::future::Future;
+
+use futures::{stream, Stream, TryStreamExt};
+
+/// Items of a list response with the token for the page after it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_page_token: Option<String>,
+}
+
+/// A list request which can be resent for the following pages.
+pub trait Paginated: Clone + Sized {
+    type Item;
+    type Error;
+
+    /// The same request asking for the page identified by `token`.
+    fn with_page_token(self, token: String) -> Self;
+
+    fn fetch_page(self) -> impl Future<Output = Result<Page<Self::Item>, Self::Error>> + Send;
+}
+
+/// Streams the items of every page, fetching the next page once the current one is drained.
+pub fn paginate<R>(request: R) -> impl Stream<Item = Result<R::Item, R::Error>>
+where
+    R: Paginated,
+{
+    let pages = stream::try_unfold(Some(request), |request| async move {
+        let Some(request) = request else {
+            return Ok(None);
+        };
+        let next = request.clone();
+        let Page {
+            items,
+            next_page_token,
+        } = request.fetch_page().await?;
+        let next = next_page_token.map(|token| next.with_page_token(token));
+        Ok(Some((stream::iter(items.into_iter().map(Ok)), next)))
+    });
+    pages.try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct MockRequest {
+        page_token: Option<String>,
+        requested: Arc<Mutex<Vec<Option<String>>>>,
+    }
+
+    impl Paginated for MockRequest {
+        type Item = u32;
+        type Error = String;
+
+        fn with_page_token(self, token: String) -> Self {
+            Self {
+                page_token: Some(token),
+                ..self
+            }
+        }
+
+        async fn fetch_page(self) -> Result<Page<u32>, String> {
+            self.requested.lock().unwrap().push(self.page_token.clone());
+            let page = match self.page_token.as_deref() {
+                None => Page {
+                    items: vec![1, 2],
+                    next_page_token: Some("second".to_string()),
+                },
+                Some("second") => Page {
+                    items: vec![3],
+                    next_page_token: None,
+                },
+                Some(token) => return Err(format!("unexpected token {token}")),
+            };
+            Ok(page)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paginate() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let request = MockRequest {
+            page_token: None,
+            requested: Arc::clone(&requested),
+        };
+        let items: Vec<u32> = paginate(request).try_collect().await.unwrap();
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(
+            *requested.lock().unwrap(),
+            vec![None, Some("second".to_string())]
+        );
+    }
+}
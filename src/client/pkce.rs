@@ -0,0 +1,74 @@
+use std::fmt;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::{Alphanumeric, DistString};
+use sha2::{Digest, Sha256};
+
+/// The PKCE `code_verifier` kept until the token exchange.
+/// `Debug` shows only the derived challenge, so it is safe to log.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PkceVerifier(String);
+
+impl PkceVerifier {
+    /// The only `code_challenge_method` produced by [`Self::challenge`].
+    pub const METHOD: &'static str = "S256";
+
+    /// A random 64-character verifier.
+    pub fn new() -> Self {
+        Self(Alphanumeric.sample_string(&mut rand::thread_rng(), 64))
+    }
+
+    /// Wraps a verifier generated elsewhere, e.g. restored from a session.
+    pub fn from_verifier<S: Into<String>>(verifier: S) -> Self {
+        Self(verifier.into())
+    }
+
+    #[inline]
+    pub fn verifier(&self) -> &str {
+        &self.0
+    }
+
+    /// `BASE64URL(SHA256(verifier))` without padding, sent as `code_challenge`.
+    pub fn challenge(&self) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(self.0.as_bytes()))
+    }
+}
+
+impl Default for PkceVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for PkceVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PkceVerifier")
+            .field("challenge", &self.challenge())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkce_verifier() {
+        // RFC 7636 Appendix B
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        let pkce = PkceVerifier::from_verifier(verifier);
+        assert_eq!(pkce.verifier(), verifier);
+        assert_eq!(
+            pkce.challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        let debug = format!("{pkce:?}");
+        assert!(!debug.contains(verifier), "{debug}");
+        assert!(debug.contains(&pkce.challenge()), "{debug}");
+
+        let random = PkceVerifier::new();
+        assert_eq!(random.verifier().len(), 64);
+        assert_ne!(random, PkceVerifier::new());
+    }
+}
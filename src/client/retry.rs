@@ -0,0 +1,265 @@
+use std::time::Duration;
+
+use crate::error::{Error, TransportPhase};
+
+use super::AuthorizedClient;
+
+/// Exponential backoff with jitter for transient failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    max_total_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_total_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// total number of attempts including the first one
+    pub fn max_attempts(self, value: u32) -> Self {
+        Self {
+            max_attempts: value.max(1),
+            ..self
+        }
+    }
+
+    pub fn base_delay(self, value: Duration) -> Self {
+        Self {
+            base_delay: value,
+            ..self
+        }
+    }
+
+    pub fn max_delay(self, value: Duration) -> Self {
+        Self {
+            max_delay: value,
+            ..self
+        }
+    }
+
+    /// Upper bound on the time slept across all retries;
+    /// a retry that would exceed it is not attempted.
+    pub fn max_total_delay(self, value: Duration) -> Self {
+        Self {
+            max_total_delay: value,
+            ..self
+        }
+    }
+
+    /// Whether another attempt may follow the `attempt`-th (0-origin) failure
+    /// after sleeping `delay`, having already slept `waited`.
+    fn allows(&self, attempt: u32, waited: Duration, delay: Duration) -> bool {
+        attempt + 1 < self.max_attempts && waited.saturating_add(delay) <= self.max_total_delay
+    }
+
+    /// Delay before the retry following the `attempt`-th (0-origin) failure,
+    /// jittered within the upper half of the exponential backoff.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        use rand::Rng;
+
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let half = backoff / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    }
+
+    /// Whether `err` is transient: a connection failure or HTTP 500/503.
+    /// OAuth errors such as `invalid_grant` are permanent.
+    pub fn is_retryable(err: &Error) -> bool {
+        match err {
+            Error::Transport {
+                phase: TransportPhase::Send,
+                ..
+            } => true,
+            Error::Status { status, .. } => matches!(
+                *status,
+                http::StatusCode::INTERNAL_SERVER_ERROR | http::StatusCode::SERVICE_UNAVAILABLE
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl AuthorizedClient {
+    /// [`AuthorizedClient::refresh`] retrying transient failures according to `policy`.
+    #[tracing::instrument(skip_all)]
+    pub async fn refresh_with_retry(self, policy: RetryPolicy) -> Result<Self, Error> {
+        let mut attempt = 0;
+        let mut waited = Duration::ZERO;
+        loop {
+            let err = match self.request_refresh().await {
+                Ok(response) => return Ok(self.refreshed_with(response)),
+                Err(err) => err,
+            };
+            let delay = policy.delay(attempt);
+            if !RetryPolicy::is_retryable(&err) || !policy.allows(attempt, waited, delay) {
+                return Err(err);
+            }
+            tracing::warn!(%err, attempt, ?delay, "retrying token refresh");
+            tokio::time::sleep(delay).await;
+            waited += delay;
+            attempt += 1;
+        }
+    }
+
+    /// [`Self::send`] waiting out `429 Too Many Requests` and `503 Service Unavailable`
+    /// responses that carry a `Retry-After` in seconds, then sending `request` again.
+    ///
+    /// The last response is returned as is once `policy` runs out of attempts, the wait
+    /// would exceed [`RetryPolicy::max_total_delay`], or the body cannot be cloned.
+    #[tracing::instrument(skip_all)]
+    pub async fn send_with_rate_limit_retry(
+        &self,
+        request: reqwest::RequestBuilder,
+        policy: RetryPolicy,
+    ) -> Result<reqwest::Response, Error> {
+        let mut attempt = 0;
+        let mut waited = Duration::ZERO;
+        loop {
+            let Some(retry) = request.try_clone() else {
+                return self.send(request).await;
+            };
+            let response = self.send(retry).await?;
+            let Some(delay) = retry_after(&response) else {
+                return Ok(response);
+            };
+            if !policy.allows(attempt, waited, delay) {
+                return Ok(response);
+            }
+            tracing::warn!(status = %response.status(), attempt, ?delay, "rate limited, retrying");
+            tokio::time::sleep(delay).await;
+            waited += delay;
+            attempt += 1;
+        }
+    }
+}
+
+/// `Retry-After` of a 429/503 response, ignoring the HTTP-date form.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let status = response.status();
+    if status != http::StatusCode::TOO_MANY_REQUESTS
+        && status != http::StatusCode::SERVICE_UNAVAILABLE
+    {
+        return None;
+    }
+    let value = response.headers().get(http::header::RETRY_AFTER)?;
+    let secs = value.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::Calendar;
+    use crate::test_util::{refreshable, token_response, MockHttpClient};
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .max_attempts(5)
+            .base_delay(Duration::from_millis(1))
+    }
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy::new()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(1000));
+        for attempt in 0..8 {
+            let expected =
+                (Duration::from_millis(100) * 2u32.pow(attempt)).min(Duration::from_millis(1000));
+            let delay = policy.delay(attempt);
+            assert!(expected / 2 <= delay && delay <= expected, "{delay:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_with_retry() {
+        let http = MockHttpClient::default();
+        let unavailable = serde_json::json!({ "error": "backend_error" });
+        http.push_json(http::StatusCode::SERVICE_UNAVAILABLE, unavailable.clone());
+        http.push_json(http::StatusCode::SERVICE_UNAVAILABLE, unavailable);
+        http.push_json(http::StatusCode::OK, token_response(&Calendar));
+        let client = refreshable(http.clone())
+            .refresh_with_retry(policy())
+            .await
+            .unwrap();
+        assert_eq!(http.take_requests().len(), 3);
+        assert_eq!(client.token().access_token, "access");
+        assert_eq!(client.token().refresh_token.as_deref(), Some("refresh/1"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_with_retry_invalid_grant() {
+        let http = MockHttpClient::default();
+        http.push_json(
+            http::StatusCode::BAD_REQUEST,
+            serde_json::json!({ "error": "invalid_grant" }),
+        );
+        let err = refreshable(http.clone())
+            .refresh_with_retry(policy())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::OAuth(ref e) if e.error == "invalid_grant"));
+        assert_eq!(http.take_requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_rate_limit_retry() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use axum::response::IntoResponse;
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let router = axum::Router::new().route(
+            "/calendar/v3/users/me/calendarList",
+            axum::routing::get({
+                let hits = Arc::clone(&hits);
+                move || async move {
+                    if hits.fetch_add(1, Ordering::SeqCst) == 0 {
+                        let headers = [(http::header::RETRY_AFTER, "1")];
+                        (http::StatusCode::TOO_MANY_REQUESTS, headers).into_response()
+                    } else {
+                        axum::Json(serde_json::json!({ "items": [] })).into_response()
+                    }
+                }
+            }),
+        );
+        let addr = crate::test_util::serve(router).await;
+        let client = refreshable(MockHttpClient::default()).with_base_url(format!("http://{addr}"));
+
+        let request = || client.get("/calendar/v3/users/me/calendarList");
+        let response = client
+            .send_with_rate_limit_retry(request(), RetryPolicy::new())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        hits.store(0, Ordering::SeqCst);
+        let policy = RetryPolicy::new().max_total_delay(Duration::from_millis(500));
+        let response = client
+            .send_with_rate_limit_retry(request(), policy)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,141 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::backend::{form_request, HttpClient};
+use crate::error::{Error, TransportEndpoint, TransportPhase};
+
+use super::{token_response, AuthorizedClient};
+
+impl AuthorizedClient {
+    /// Revokes the grant at [`crate::Endpoints::revoke_uri`].
+    /// The refresh token is revoked when present, which also invalidates the access token.
+    #[tracing::instrument(skip_all)]
+    pub async fn revoke(&self) -> Result<(), Error> {
+        let token = self
+            .token
+            .refresh_token()
+            .unwrap_or(&self.token.access_token);
+        revoke(self.http.as_ref(), &self.endpoints.revoke_uri, token).await
+    }
+
+    /// Wraps `self` so that dropping it revokes the token, e.g. for a one-off CLI session.
+    ///
+    /// The revocation is best-effort: it is spawned on the current Tokio runtime
+    /// and nobody waits for it. Dropped outside a runtime, or right before the runtime
+    /// shuts down, nothing may be sent. Call [`Self::revoke`] where the outcome matters.
+    pub fn revoke_on_drop(self) -> RevokeOnDrop {
+        RevokeOnDrop(Some(self))
+    }
+}
+
+async fn revoke(http: &dyn HttpClient, uri: &str, token: &str) -> Result<(), Error> {
+    use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+    let body = format!("token={}", utf8_percent_encode(token, NON_ALPHANUMERIC));
+    let request = form_request(uri, body)?;
+    let response = http
+        .execute(request)
+        .await
+        .map_err(|err| Error::transport(TransportEndpoint::Revoke, TransportPhase::Send, err))?;
+    if response.status().is_success() {
+        return Ok(());
+    }
+    token_response::<serde_json::Value>(response, TransportEndpoint::Revoke).map(|_| ())
+}
+
+/// An [`AuthorizedClient`] revoking its token when dropped,
+/// created by [`AuthorizedClient::revoke_on_drop`].
+pub struct RevokeOnDrop(Option<AuthorizedClient>);
+
+impl RevokeOnDrop {
+    /// Takes the client back without revoking.
+    pub fn into_inner(mut self) -> AuthorizedClient {
+        self.0.take().expect("client is only taken once")
+    }
+}
+
+impl Deref for RevokeOnDrop {
+    type Target = AuthorizedClient;
+
+    fn deref(&self) -> &AuthorizedClient {
+        self.0.as_ref().expect("client is only taken once")
+    }
+}
+
+impl Drop for RevokeOnDrop {
+    fn drop(&mut self) {
+        let Some(client) = self.0.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!("no Tokio runtime to revoke the token on");
+            return;
+        };
+        let http = Arc::clone(&client.http);
+        let uri = client.endpoints.revoke_uri.clone();
+        let token = client
+            .token
+            .refresh_token()
+            .unwrap_or(&client.token.access_token)
+            .to_string();
+        runtime.spawn(async move {
+            if let Err(err) = revoke(http.as_ref(), &uri, &token).await {
+                let err = &err as &dyn std::error::Error;
+                tracing::warn!(err, "could not revoke token on drop");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{refreshable, MockHttpClient};
+
+    #[tokio::test]
+    async fn test_revoke() {
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, serde_json::json!({}));
+        http.push_json(
+            http::StatusCode::BAD_REQUEST,
+            serde_json::json!({
+                "error": "invalid_token",
+                "error_description": "Token expired or revoked"
+            }),
+        );
+        let client = refreshable(http.clone());
+        client.revoke().await.unwrap();
+        let err = client.revoke().await.unwrap_err();
+        assert!(matches!(err, Error::OAuth(_)), "{err:?}");
+
+        let requests = http.take_requests();
+        assert_eq!(requests[0].uri(), "https://oauth2.googleapis.com/revoke");
+        assert_eq!(requests[0].body(), b"token=refresh%2F1");
+    }
+
+    #[tokio::test]
+    async fn test_revoke_on_drop() {
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, serde_json::json!({}));
+        let guard = refreshable(http.clone()).revoke_on_drop();
+        assert_eq!(guard.token().access_token(), "stale");
+        assert!(http.take_requests().is_empty());
+
+        drop(guard);
+        let mut requests = Vec::new();
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+            requests = http.take_requests();
+            if !requests.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body(), b"token=refresh%2F1");
+
+        let client = refreshable(http.clone()).revoke_on_drop().into_inner();
+        drop(client);
+        tokio::task::yield_now().await;
+        assert!(http.take_requests().is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{form_request, HttpClient};
+use crate::endpoints::Endpoints;
+use crate::error::{Error, TransportEndpoint, TransportPhase};
+use crate::scope::{Scope, SpaceDelimitedScope};
+use crate::secret::ServiceAccountKey;
+
+use super::{token_response, unix_now, AuthorizedClient, Bearer, JwtBearer, Token};
+
+/// https://developers.google.com/identity/protocols/oauth2/service-account#httprest
+#[derive(Clone)]
+pub struct ServiceAccountClient {
+    key: ServiceAccountKey,
+    scope: SpaceDelimitedScope,
+    endpoints: Endpoints,
+    http: Arc<dyn HttpClient>,
+}
+
+/// JWT claim set of the assertion
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Claims {
+    pub iss: String,
+    pub scope: SpaceDelimitedScope,
+    pub aud: String,
+    pub iat: u64,
+    pub exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u32,
+    token_type: Bearer,
+}
+
+impl ServiceAccountClient {
+    /// maximum lifetime of an assertion accepted by Google
+    pub const ASSERTION_LIFETIME: u64 = 3600;
+
+    pub fn new<S: Scope>(key: ServiceAccountKey, scope: S) -> Self {
+        Self {
+            endpoints: Endpoints::default().token_uri(key.token_uri.clone()),
+            key,
+            scope: scope.space_delimited(),
+            http: Arc::new(reqwest::Client::new()),
+        }
+    }
+
+    /// Overrides the endpoints otherwise taken from the key and Google's defaults.
+    /// The authorized client inherits them.
+    /// The assertion is still addressed to the key's `token_uri`.
+    pub fn with_endpoints(self, endpoints: Endpoints) -> Self {
+        Self { endpoints, ..self }
+    }
+
+    #[inline]
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    pub fn with_http_client<H: HttpClient>(self, http: H) -> Self {
+        Self {
+            http: Arc::new(http),
+            ..self
+        }
+    }
+
+    pub fn claims(&self, iat: u64) -> Claims {
+        let Self { key, scope, .. } = self;
+        Claims {
+            iss: key.client_email.clone(),
+            scope: scope.clone(),
+            aud: key.token_uri.clone(),
+            iat,
+            exp: iat + Self::ASSERTION_LIFETIME,
+        }
+    }
+
+    /// RS256-signed JWT assertion issued at `iat` (seconds since the UNIX epoch)
+    pub fn assertion(&self, iat: u64) -> jsonwebtoken::errors::Result<String> {
+        use jsonwebtoken::{Algorithm, EncodingKey, Header};
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = self.key.private_key_id.clone();
+        let key = EncodingKey::from_rsa_pem(self.key.private_key.as_bytes())?;
+        jsonwebtoken::encode(&header, &self.claims(iat), &key)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn authorize(&self) -> Result<AuthorizedClient, Error> {
+        use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+        let iat = unix_now();
+        let assertion = self.assertion(iat)?;
+        let grant_type = JwtBearer::new().to_string();
+        let grant_type = utf8_percent_encode(&grant_type, NON_ALPHANUMERIC);
+        let assertion = utf8_percent_encode(&assertion, NON_ALPHANUMERIC);
+        let body = format!("grant_type={grant_type}&assertion={assertion}");
+        let request = form_request(&self.endpoints.token_uri, body)?;
+        let response = self.http.execute(request).await.map_err(|err| {
+            tracing::error!(err, "could not send request");
+            Error::transport(TransportEndpoint::Token, TransportPhase::Send, err)
+        })?;
+        let response: TokenResponse = token_response(response, TransportEndpoint::Token)
+            .inspect_err(|err| {
+                let err = err as &dyn std::error::Error;
+                tracing::error!(err, "could not parse token response");
+            })?;
+        let TokenResponse {
+            access_token,
+            expires_in,
+            token_type,
+        } = response;
+        let token = Token {
+            access_token: access_token.into(),
+            expires_in,
+            refresh_token: None,
+            scope: self.scope.clone(),
+            token_type,
+            obtained_at: iat,
+            id_token: None,
+        };
+        Ok(AuthorizedClient::from_token(token).with_endpoints(self.endpoints.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = include_str!("../../testdata/service_account_key.pem");
+    const PUBLIC_KEY: &str = include_str!("../../testdata/service_account_key.pub.pem");
+
+    fn key() -> ServiceAccountKey {
+        ServiceAccountKey {
+            r#type: "service_account".to_string(),
+            private_key_id: Some("key-id".to_string()),
+            private_key: PRIVATE_KEY.into(),
+            client_email: "sa@project-id.iam.gserviceaccount.com".to_string(),
+            token_uri: "https://oauth2.googleapis.com/token".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_claims() {
+        let scope = crate::combine_scope![calendar, calendar.readonly];
+        let client = ServiceAccountClient::new(key(), scope);
+        let claims = client.claims(1_700_000_000);
+        assert_eq!(claims.iss, "sa@project-id.iam.gserviceaccount.com");
+        assert_eq!(claims.scope, scope.space_delimited());
+        assert_eq!(claims.aud, "https://oauth2.googleapis.com/token");
+        assert_eq!(claims.iat, 1_700_000_000);
+        assert_eq!(claims.exp, 1_700_003_600);
+    }
+
+    #[test]
+    fn test_assertion() {
+        use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+
+        let client = ServiceAccountClient::new(key(), crate::scope::Calendar);
+        let iat = unix_now();
+        let assertion = client.assertion(iat).unwrap();
+
+        let header = jsonwebtoken::decode_header(&assertion).unwrap();
+        assert_eq!(header.alg, Algorithm::RS256);
+        assert_eq!(header.typ.as_deref(), Some("JWT"));
+        assert_eq!(header.kid.as_deref(), Some("key-id"));
+
+        let key = DecodingKey::from_rsa_pem(PUBLIC_KEY.as_bytes()).unwrap();
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&["https://oauth2.googleapis.com/token"]);
+        validation.set_issuer(&["sa@project-id.iam.gserviceaccount.com"]);
+        let data = jsonwebtoken::decode::<Claims>(&assertion, &key, &validation).unwrap();
+        assert_eq!(data.claims, client.claims(iat));
+    }
+
+    #[tokio::test]
+    async fn test_authorize_with_endpoints() {
+        use crate::scope::Calendar;
+        use crate::test_util::{token_response, MockHttpClient};
+
+        let http = MockHttpClient::default();
+        http.push_json(http::StatusCode::OK, token_response(&Calendar));
+        let client = ServiceAccountClient::new(key(), Calendar);
+        assert_eq!(
+            client.endpoints().token_uri,
+            "https://oauth2.googleapis.com/token"
+        );
+        let endpoints = Endpoints::default()
+            .token_uri("http://127.0.0.1:8080/token")
+            .revoke_uri("http://127.0.0.1:8080/revoke");
+        let client = client
+            .with_endpoints(endpoints.clone())
+            .with_http_client(http.clone());
+        let authorized = client.authorize().await.unwrap();
+        assert_eq!(authorized.token().access_token(), "access");
+        assert_eq!(authorized.endpoints(), &endpoints);
+        let requests = http.take_requests();
+        assert_eq!(requests[0].uri(), "http://127.0.0.1:8080/token");
+        assert_eq!(client.claims(0).aud, "https://oauth2.googleapis.com/token");
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, RwLock, RwLockReadGuard};
+
+use crate::error::Error;
+
+use super::AuthorizedClient;
+
+/// An [`AuthorizedClient`] shareable across tasks, refreshed in place.
+#[derive(Clone)]
+pub struct SharedAuthorizedClient {
+    client: Arc<RwLock<AuthorizedClient>>,
+    refreshing: Arc<Mutex<()>>,
+}
+
+impl SharedAuthorizedClient {
+    pub fn new(client: AuthorizedClient) -> Self {
+        Self {
+            client: Arc::new(RwLock::new(client)),
+            refreshing: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Locks the client for reading. Requests built from it carry the current token.
+    pub async fn read(&self) -> RwLockReadGuard<'_, AuthorizedClient> {
+        self.client.read().await
+    }
+
+    pub async fn request(&self, method: http::Method, uri: &str) -> reqwest::RequestBuilder {
+        self.read().await.request(method, uri)
+    }
+
+    /// Refreshes the token under the lock.
+    /// Concurrent callers wait for a single refresh instead of launching their own.
+    #[tracing::instrument(skip_all)]
+    pub async fn refresh_in_place(&self) -> Result<(), Error> {
+        let stale = self.read().await.token.access_token.clone();
+        let _refreshing = self.refreshing.lock().await;
+        let client = self.read().await.clone();
+        if client.token.access_token != stale {
+            tracing::debug!("already refreshed by another caller");
+            return Ok(());
+        }
+        let client = client.refresh().await?;
+        *self.client.write().await = client;
+        Ok(())
+    }
+}
+
+impl From<AuthorizedClient> for SharedAuthorizedClient {
+    fn from(value: AuthorizedClient) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::scope::Calendar;
+    use crate::test_util::{secret, serve, token, token_response};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_refresh_in_place_single_flight() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let router = axum::Router::new().route(
+            "/token",
+            axum::routing::post({
+                let hits = Arc::clone(&hits);
+                move || async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    axum::Json(token_response(&Calendar))
+                }
+            }),
+        );
+        let addr = serve(router).await;
+        let mut secret = secret();
+        secret.token_uri = format!("http://{addr}/token");
+        let token = token(&Calendar)
+            .access_token("stale")
+            .refresh_token("refresh")
+            .build();
+        let client = SharedAuthorizedClient::new(AuthorizedClient::new(secret, token));
+
+        let tasks = 16;
+        let barrier = Arc::new(tokio::sync::Barrier::new(tasks));
+        let handles: Vec<_> = (0..tasks)
+            .map(|_| {
+                let client = client.clone();
+                let barrier = Arc::clone(&barrier);
+                tokio::spawn(async move {
+                    barrier.wait().await;
+                    client.refresh_in_place().await.unwrap();
+                    client.read().await.token().access_token.clone()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), "access");
+        }
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let request = client
+            .request(http::Method::GET, "/")
+            .await
+            .build()
+            .unwrap();
+        let authorization = request.headers().get(http::header::AUTHORIZATION).unwrap();
+        assert_eq!(authorization, "Bearer access");
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, TransportEndpoint, TransportPhase};
+use crate::scope::SpaceDelimitedScope;
+
+use super::{misc, token_response, AuthorizedClient};
+
+/// https://developers.google.com/identity/sign-in/web/backend-auth#calling-the-tokeninfo-endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct TokenInfo {
+    pub scope: SpaceDelimitedScope,
+    #[serde(deserialize_with = "misc::deserialize_u32_lenient")]
+    pub expires_in: u32,
+    pub aud: String,
+    #[serde(default)]
+    pub sub: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+impl AuthorizedClient {
+    /// Validates the current access token, returning its remaining lifetime and granted scope.
+    #[tracing::instrument(skip_all)]
+    pub async fn token_info(&self) -> Result<TokenInfo, Error> {
+        use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+        let access_token = utf8_percent_encode(&self.token.access_token, NON_ALPHANUMERIC);
+        let url = format!(
+            "{}?access_token={access_token}",
+            self.endpoints.tokeninfo_uri
+        );
+        let request = http::Request::get(url).body(Vec::new())?;
+        let response = self.http.execute(request).await.map_err(|err| {
+            Error::transport(TransportEndpoint::TokenInfo, TransportPhase::Send, err)
+        })?;
+        token_response(response, TransportEndpoint::TokenInfo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endpoints::Endpoints;
+    use crate::scope::{Calendar, CalendarReadonly, Scope, SingleScope};
+    use crate::test_util::{authorized, serve};
+
+    #[tokio::test]
+    async fn test_token_info() {
+        let router = axum::Router::new().route(
+            "/tokeninfo",
+            axum::routing::get(
+                |axum::extract::RawQuery(query): axum::extract::RawQuery| async move {
+                    assert_eq!(query.as_deref(), Some("access_token=access"));
+                    axum::Json(serde_json::json!({
+                        "azp": "client_id",
+                        "aud": "client_id",
+                        "sub": "1234567890",
+                        "scope": format!("{} {}", Calendar::STR, CalendarReadonly::STR),
+                        "exp": "1700003599",
+                        "expires_in": "3599",
+                        "email": "user@example.com",
+                        "email_verified": "true",
+                        "access_type": "offline"
+                    }))
+                },
+            ),
+        );
+        let addr = serve(router).await;
+        let endpoints = Endpoints::default().tokeninfo_uri(format!("http://{addr}/tokeninfo"));
+        let client = authorized(&Calendar).with_endpoints(endpoints);
+        let info = client.token_info().await.unwrap();
+        let scope: Vec<_> = info.scope.iter().copied().collect();
+        assert_eq!(scope, vec![Calendar.as_dyn(), CalendarReadonly.as_dyn()]);
+        assert_eq!(info.expires_in, 3599);
+        assert_eq!(info.aud, "client_id");
+        assert_eq!(info.sub.as_deref(), Some("1234567890"));
+        assert_eq!(info.email.as_deref(), Some("user@example.com"));
+    }
+
+    #[test]
+    fn test_token_info_bincode() {
+        let info = TokenInfo {
+            scope: Calendar.space_delimited(),
+            expires_in: 3599,
+            aud: "client_id".to_string(),
+            sub: None,
+            email: Some("user@example.com".to_string()),
+        };
+        let bytes = bincode::serialize(&info).unwrap();
+        assert_eq!(bincode::deserialize::<TokenInfo>(&bytes).unwrap(), info);
+
+        let info = TokenInfo {
+            scope: Default::default(),
+            ..info
+        };
+        let bytes = bincode::serialize(&info).unwrap();
+        assert_eq!(bincode::deserialize::<TokenInfo>(&bytes).unwrap(), info);
+    }
+}
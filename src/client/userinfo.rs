@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::scope::{Openid, UserinfoEmail, UserinfoProfile};
+
+use super::api::read_json;
+use super::{AuthorizedClient, InsufficientScopeError};
+
+/// https://developers.google.com/identity/openid-connect/openid-connect#obtaininguserprofileinformation
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct UserInfo {
+    pub sub: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub given_name: Option<String>,
+    #[serde(default)]
+    pub family_name: Option<String>,
+    #[serde(default)]
+    pub picture: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+impl AuthorizedClient {
+    /// Fetches the profile of the signed-in user.
+    /// Requires one of the `openid`, `email` or `profile` scopes.
+    #[tracing::instrument(skip_all)]
+    pub async fn userinfo(&self) -> Result<UserInfo, Error> {
+        let token = self.token();
+        if !(token.has_scope(&Openid)
+            || token.has_scope(&UserinfoEmail)
+            || token.has_scope(&UserinfoProfile))
+        {
+            let required = vec![Openid::STR, UserinfoEmail::STR, UserinfoProfile::STR];
+            let granted = token.scope().clone();
+            return Err(InsufficientScopeError::any(required, granted).into());
+        }
+        let request = self.inner.get(&self.endpoints.userinfo_uri);
+        let request = self.decorate_request(request);
+        read_json(self.send(request).await?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endpoints::Endpoints;
+    use crate::error::GoogleApiError;
+    use crate::scope::{Calendar, Openid};
+    use crate::test_util::{authorized, serve};
+
+    #[test]
+    fn test_userinfo_de() {
+        let payload = r#"{
+            "sub": "1234567890",
+            "name": "Jane Doe",
+            "given_name": "Jane",
+            "family_name": "Doe",
+            "picture": "https://lh3.googleusercontent.com/a/photo",
+            "email": "jane@example.com",
+            "email_verified": true,
+            "locale": "en"
+        }"#;
+        let info: UserInfo = serde_json::from_str(payload).unwrap();
+        assert_eq!(info.sub, "1234567890");
+        assert_eq!(info.name.as_deref(), Some("Jane Doe"));
+        assert_eq!(info.given_name.as_deref(), Some("Jane"));
+        assert_eq!(info.family_name.as_deref(), Some("Doe"));
+        assert_eq!(info.email.as_deref(), Some("jane@example.com"));
+        assert_eq!(info.email_verified, Some(true));
+        assert_eq!(info.locale.as_deref(), Some("en"));
+
+        let info: UserInfo = serde_json::from_str(r#"{"sub": "1234567890"}"#).unwrap();
+        assert_eq!(info.email, None);
+    }
+
+    #[tokio::test]
+    async fn test_userinfo_with_endpoints() {
+        let router = axum::Router::new().route(
+            "/userinfo",
+            axum::routing::get(|headers: http::HeaderMap| async move {
+                assert_eq!(headers[http::header::AUTHORIZATION], "Bearer access");
+                axum::Json(serde_json::json!({ "sub": "1234567890" }))
+            }),
+        );
+        let addr = serve(router).await;
+        let endpoints = Endpoints::default().userinfo_uri(format!("http://{addr}/userinfo"));
+        let client = authorized(&Openid).with_endpoints(endpoints);
+        let info = client.userinfo().await.unwrap();
+        assert_eq!(info.sub, "1234567890");
+    }
+
+    #[tokio::test]
+    async fn test_userinfo_api_error() {
+        let router = axum::Router::new().route(
+            "/userinfo",
+            axum::routing::get(|| async {
+                let body = serde_json::json!({
+                    "error": {
+                        "code": 401,
+                        "message": "Request had invalid authentication credentials.",
+                        "status": "UNAUTHENTICATED"
+                    }
+                });
+                (http::StatusCode::UNAUTHORIZED, axum::Json(body))
+            }),
+        );
+        let addr = serve(router).await;
+        let endpoints = Endpoints::default().userinfo_uri(format!("http://{addr}/userinfo"));
+        let client = authorized(&Openid).with_endpoints(endpoints);
+        let err = client.userinfo().await.unwrap_err();
+        assert!(
+            matches!(err, Error::Api(GoogleApiError { code: 401, .. })),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_userinfo_insufficient_scope() {
+        let client = authorized(&Calendar);
+        let err = client.userinfo().await.unwrap_err();
+        assert!(matches!(err, Error::InsufficientScope(_)), "{err:?}");
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::secret::WebClientSecret;
+
+/// URLs of the OAuth 2.0 endpoints the clients talk to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Endpoints {
+    pub auth_uri: String,
+    pub token_uri: String,
+    pub revoke_uri: String,
+    /// JWKS used to verify ID tokens
+    pub certs_uri: String,
+    pub tokeninfo_uri: String,
+    #[serde(default = "default_userinfo_uri")]
+    pub userinfo_uri: String,
+}
+
+fn default_userinfo_uri() -> String {
+    Endpoints::USERINFO_URI.to_string()
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            auth_uri: Self::AUTH_URI.to_string(),
+            token_uri: Self::TOKEN_URI.to_string(),
+            revoke_uri: Self::REVOKE_URI.to_string(),
+            certs_uri: Self::CERTS_URI.to_string(),
+            tokeninfo_uri: Self::TOKENINFO_URI.to_string(),
+            userinfo_uri: Self::USERINFO_URI.to_string(),
+        }
+    }
+}
+
+impl Endpoints {
+    pub const AUTH_URI: &'static str = "https://accounts.google.com/o/oauth2/auth";
+    pub const TOKEN_URI: &'static str = "https://oauth2.googleapis.com/token";
+    pub const REVOKE_URI: &'static str = "https://oauth2.googleapis.com/revoke";
+    pub const CERTS_URI: &'static str = "https://www.googleapis.com/oauth2/v3/certs";
+    pub const TOKENINFO_URI: &'static str = "https://oauth2.googleapis.com/tokeninfo";
+    pub const USERINFO_URI: &'static str = "https://www.googleapis.com/oauth2/v3/userinfo";
+
+    /// Google's endpoints, with `auth_uri` and `token_uri` taken from `secret`.
+    pub fn from_secret(secret: &WebClientSecret) -> Self {
+        Self {
+            auth_uri: secret.auth_uri.clone(),
+            token_uri: secret.token_uri.clone(),
+            ..Self::default()
+        }
+    }
+
+    pub fn auth_uri<S: Into<String>>(self, value: S) -> Self {
+        Self {
+            auth_uri: value.into(),
+            ..self
+        }
+    }
+
+    pub fn token_uri<S: Into<String>>(self, value: S) -> Self {
+        Self {
+            token_uri: value.into(),
+            ..self
+        }
+    }
+
+    pub fn revoke_uri<S: Into<String>>(self, value: S) -> Self {
+        Self {
+            revoke_uri: value.into(),
+            ..self
+        }
+    }
+
+    pub fn certs_uri<S: Into<String>>(self, value: S) -> Self {
+        Self {
+            certs_uri: value.into(),
+            ..self
+        }
+    }
+
+    pub fn tokeninfo_uri<S: Into<String>>(self, value: S) -> Self {
+        Self {
+            tokeninfo_uri: value.into(),
+            ..self
+        }
+    }
+
+    pub fn userinfo_uri<S: Into<String>>(self, value: S) -> Self {
+        Self {
+            userinfo_uri: value.into(),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("redirect_uri is required")]
+    MissingRedirectUri,
+    #[error("redirect_uri {redirect_uri} is not registered, expected one of {allowed:?}")]
+    UnregisteredRedirectUri {
+        redirect_uri: String,
+        allowed: Vec<String>,
+    },
+    #[error("invalid scope: {0}")]
+    InvalidScope(String),
+    #[error("scope is empty")]
+    EmptyScope,
+    #[error("secret is required")]
+    MissingSecret,
+    #[error("refresh_token is not provided")]
+    MissingRefreshToken,
+    #[error(
+        "authorization server did not issue a refresh_token; \
+         add prompt=consent to the authorization URL to force one"
+    )]
+    RefreshTokenNotIssued,
+    #[error("response_type={0} returns no code to exchange for a token")]
+    CodeNotRequested(crate::client::ResponseType),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("could not build request: {0}")]
+    Request(#[from] http::Error),
+    #[error("failed to {phase} {endpoint}")]
+    Transport {
+        endpoint: TransportEndpoint,
+        phase: TransportPhase,
+        #[source]
+        source: BoxError,
+    },
+    #[error("authorization server returned an error: {0}")]
+    OAuth(#[from] TokenError),
+    #[error("authorization was not completed: {0}")]
+    Callback(#[from] crate::route::CallbackError),
+    #[error("server responded with {status}: {body}")]
+    Status {
+        status: http::StatusCode,
+        body: String,
+    },
+    #[error("expected JSON but server responded with {status} and {content_type}: {snippet}")]
+    UnexpectedContentType {
+        status: http::StatusCode,
+        content_type: String,
+        /// head of the body
+        snippet: String,
+    },
+    #[error("Google API returned an error: {0}")]
+    Api(#[from] GoogleApiError),
+    #[error("malformed batch response: {0}")]
+    Batch(String),
+    /// The server answered `410 Gone`; discard the sync token and list everything again.
+    #[error("sync token is no longer valid, a full sync is required")]
+    SyncTokenExpired,
+    #[error(transparent)]
+    InsufficientScope(#[from] crate::client::InsufficientScopeError),
+    #[error("id_token is not provided")]
+    MissingIdToken,
+    #[error("nonce in id_token does not match")]
+    NonceMismatch,
+    #[error(transparent)]
+    Jwt(#[from] jsonwebtoken::errors::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Custom(BoxError),
+}
+
+/// Which server a [`Error::Transport`] occurred talking to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TransportEndpoint {
+    Token,
+    Revoke,
+    TokenInfo,
+    /// Google APIs such as Calendar
+    Api,
+}
+
+impl fmt::Display for TransportEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Token => "token endpoint",
+            Self::Revoke => "revoke endpoint",
+            Self::TokenInfo => "tokeninfo endpoint",
+            Self::Api => "API",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Where in the exchange a [`Error::Transport`] occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransportPhase {
+    /// connecting, sending the request or receiving the response
+    Send,
+    /// reading the response body into the expected type
+    Decode,
+}
+
+impl fmt::Display for TransportPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Send => f.write_str("send request to"),
+            Self::Decode => f.write_str("decode response from"),
+        }
+    }
+}
+
+impl Error {
+    pub(crate) fn transport<E>(
+        endpoint: TransportEndpoint,
+        phase: TransportPhase,
+        source: E,
+    ) -> Self
+    where
+        E: Into<BoxError>,
+    {
+        Self::Transport {
+            endpoint,
+            phase,
+            source: source.into(),
+        }
+    }
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc6749#section-5.2
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, thiserror::Error)]
+pub struct TokenError {
+    pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_uri: Option<String>,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.error)?;
+        if let Some(description) = &self.error_description {
+            write!(f, " ({description})")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `error` object of Google's JSON error responses.
+/// https://cloud.google.com/apis/design/errors#http_mapping
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, thiserror::Error)]
+#[error("{code} {message}")]
+pub struct GoogleApiError {
+    pub code: u16,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GoogleApiErrorItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleApiErrorItem {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl GoogleApiError {
+    /// Parses `{"error": {...}}`, returning `None` if `body` is not in that shape.
+    pub fn from_body(body: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Envelope {
+            error: GoogleApiError,
+        }
+
+        let Envelope { error } = serde_json::from_str(body).ok()?;
+        Some(error)
+    }
+}
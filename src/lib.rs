@@ -1,9 +1,45 @@
+mod backend;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod client;
+mod endpoints;
+mod error;
 mod route;
 pub mod scope;
 mod secret;
+mod store;
+#[cfg(test)]
+mod test_util;
 
-pub use client::{AuthorizedClient, UnauthorizedClient};
-pub use route::make_router;
+pub use backend::{HttpClient, HttpRequest, HttpResponse};
+#[cfg(feature = "calendar")]
+pub use client::calendar;
+#[cfg(feature = "drive")]
+pub use client::drive;
+pub use client::{
+    paginate, refresh_token, AuthorizationRequest, AuthorizedClient, ClientConfig, IdTokenClaims,
+    InsufficientScopeError, Page, Paginated, PkceVerifier, Prompt, PromptError, PromptSet,
+    ResponseType, RetryPolicy, RevokeOnDrop, ScopeChange, ServiceAccountClaims,
+    ServiceAccountClient, SharedAuthorizedClient, Token, TokenBuilder, TokenInfo,
+    UnauthorizedClient, UserInfo,
+};
+pub use endpoints::Endpoints;
+pub use error::{
+    Error, GoogleApiError, GoogleApiErrorItem, Result, TokenError, TransportEndpoint,
+    TransportPhase,
+};
+pub use route::{
+    make_callback_router, make_router, parse_callback, CallbackError, CallbackQuery,
+    CallbackResult, CallbackState, SessionStore,
+};
 pub use scope::{BoxScope, Scope};
-pub use secret::{ClientSecret, WebClientSecret};
+pub use secret::{
+    ClientSecret, SecretString, ServiceAccountKey, ValidationError, WebClientSecret,
+    WebClientSecretBuilder,
+};
+pub use store::{FileTokenStore, TokenStore};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use paste::paste;
+}
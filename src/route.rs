@@ -1,5 +1,496 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::{FromRef, FromRequestParts, Query, State};
 use axum::{routing, Router};
+use serde::Deserialize;
+use tokio::sync::{mpsc, RwLock};
+
+use crate::client::{AuthorizedClient, Token};
+use crate::secret::WebClientSecret;
 
 pub fn make_router() -> Router {
-    Router::new().route("/ping", routing::get(|| async { "pong" }))
+    Router::new()
+        .route("/ping", routing::get(|| async { "pong" }))
+        .route("/healthz", routing::get(healthz))
+}
+
+async fn healthz() -> axum::Json<serde_json::Value> {
+    axum::Json(serde_json::json!({
+        "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
+/// State of the route built by [`make_callback_router`].
+#[derive(Debug, Clone)]
+pub struct CallbackState {
+    expected_state: String,
+    code_tx: mpsc::UnboundedSender<String>,
+}
+
+impl CallbackState {
+    /// `expected_state` is the value passed to
+    /// [`UnauthorizedClient::generate_url_with_state`](crate::UnauthorizedClient::generate_url_with_state);
+    /// accepted codes are sent to `code_tx`.
+    pub fn new<S: Into<String>>(expected_state: S, code_tx: mpsc::UnboundedSender<String>) -> Self {
+        Self {
+            expected_state: expected_state.into(),
+            code_tx,
+        }
+    }
+}
+
+/// Serves the redirect URI at `path`, rejecting callbacks whose `state` does not match
+/// with `400 Bad Request`.
+pub fn make_callback_router(path: &str, state: CallbackState) -> Router {
+    Router::new()
+        .route(path, routing::get(callback))
+        .with_state(state)
+}
+
+#[tracing::instrument(skip_all)]
+async fn callback(
+    State(state): State<CallbackState>,
+    Query(query): Query<CallbackQuery>,
+) -> (http::StatusCode, String) {
+    let code = match query.into_code_checked(&state.expected_state) {
+        Ok(code) => code,
+        Err(e) => {
+            tracing::warn!("rejected callback: {e}");
+            return (http::StatusCode::BAD_REQUEST, e.to_string());
+        }
+    };
+    let Ok(()) = state.code_tx.send(code) else {
+        tracing::error!("mpsc channel error");
+        let status = http::StatusCode::INTERNAL_SERVER_ERROR;
+        return (status, "channel error".to_string());
+    };
+    (http::StatusCode::OK, "authorized".to_string())
+}
+
+/// Tokens of signed-in users, keyed by a session cookie,
+/// [`SessionStore::COOKIE`] unless set with [`SessionStore::with_cookie`].
+/// Provide it and the app's [`WebClientSecret`] through [`FromRef`]
+/// to extract an [`AuthorizedClient`] in handlers.
+///
+/// Sessions live in memory and are lost on restart. Without [`SessionStore::with_ttl`]
+/// they are kept until removed; with it, call [`SessionStore::evict_expired`]
+/// periodically to bound the memory used.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    tokens: Arc<RwLock<HashMap<String, (Token, Instant)>>>,
+    cookie: Arc<str>,
+    ttl: Option<Duration>,
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self {
+            tokens: Default::default(),
+            cookie: Self::COOKIE.into(),
+            ttl: None,
+        }
+    }
+}
+
+impl SessionStore {
+    /// The default session cookie name.
+    pub const COOKIE: &'static str = "session";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the session id from the cookie `name` instead of [`SessionStore::COOKIE`].
+    pub fn with_cookie<S: Into<String>>(self, name: S) -> Self {
+        Self {
+            cookie: name.into().into(),
+            ..self
+        }
+    }
+
+    /// Forgets sessions `ttl` after they were last inserted.
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn cookie(&self) -> &str {
+        &self.cookie
+    }
+
+    fn is_live(&self, inserted_at: Instant) -> bool {
+        self.ttl.is_none_or(|ttl| inserted_at.elapsed() < ttl)
+    }
+
+    pub async fn insert<S: Into<String>>(&self, session_id: S, token: Token) {
+        let entry = (token, Instant::now());
+        self.tokens.write().await.insert(session_id.into(), entry);
+    }
+
+    /// The token of the session, unless it has outlived the TTL.
+    pub async fn get(&self, session_id: &str) -> Option<Token> {
+        let tokens = self.tokens.read().await;
+        let (token, inserted_at) = tokens.get(session_id)?;
+        self.is_live(*inserted_at).then(|| token.clone())
+    }
+
+    pub async fn remove(&self, session_id: &str) -> Option<Token> {
+        let (token, _) = self.tokens.write().await.remove(session_id)?;
+        Some(token)
+    }
+
+    /// Removes the sessions that outlived the TTL, returning how many were removed.
+    pub async fn evict_expired(&self) -> usize {
+        let mut tokens = self.tokens.write().await;
+        let before = tokens.len();
+        tokens.retain(|_, (_, inserted_at)| self.is_live(*inserted_at));
+        before - tokens.len()
+    }
+}
+
+fn session_id<'a>(parts: &'a http::request::Parts, cookie_name: &str) -> Option<&'a str> {
+    parts
+        .headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .find_map(|cookie| {
+            let (name, value) = cookie.trim().split_once('=')?;
+            (name == cookie_name).then_some(value)
+        })
+}
+
+/// Rejects with `401 Unauthorized` if there is no stored token for the session,
+/// or if the stored one has expired and could not be refreshed.
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for AuthorizedClient
+where
+    S: Send + Sync,
+    SessionStore: FromRef<S>,
+    WebClientSecret: FromRef<S>,
+{
+    type Rejection = (http::StatusCode, &'static str);
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        const UNAUTHORIZED: (http::StatusCode, &str) =
+            (http::StatusCode::UNAUTHORIZED, "not signed in");
+
+        let store = SessionStore::from_ref(state);
+        let session_id = session_id(parts, store.cookie()).ok_or(UNAUTHORIZED)?;
+        let token = store.get(session_id).await.ok_or(UNAUTHORIZED)?;
+        let client = AuthorizedClient::new(WebClientSecret::from_ref(state), token);
+        if !client.token().is_expired() {
+            return Ok(client);
+        }
+        let client = client.refresh().await.map_err(|e| {
+            tracing::warn!("could not refresh session token: {e}");
+            UNAUTHORIZED
+        })?;
+        store.insert(session_id, client.token().clone()).await;
+        Ok(client)
+    }
+}
+
+/// Query parameters the authorization server appends to the redirect URI.
+/// Usable with [`axum::extract::Query`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize)]
+pub struct CallbackQuery {
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl CallbackQuery {
+    /// Parses an `application/x-www-form-urlencoded` query string, ignoring unknown keys.
+    pub fn parse(query: &str) -> Self {
+        let mut this = Self::default();
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            let field = match key.as_ref() {
+                "code" => &mut this.code,
+                "state" => &mut this.state,
+                "error" => &mut this.error,
+                _ => continue,
+            };
+            *field = Some(value.into_owned());
+        }
+        this
+    }
+
+    /// The authorization code, or the reason the server did not issue one.
+    pub fn into_code(self) -> Result<String, CallbackError> {
+        let Self { code, error, .. } = self;
+        if let Some(error) = error {
+            let Ok(error) = error.parse();
+            return Err(error);
+        }
+        code.ok_or(CallbackError::MissingCode)
+    }
+
+    /// Like [`Self::into_code`], additionally requiring `state` to equal `expected_state`.
+    pub fn into_code_checked(self, expected_state: &str) -> Result<String, CallbackError> {
+        if self.state.as_deref() != Some(expected_state) {
+            return Err(CallbackError::StateMismatch);
+        }
+        self.into_code()
+    }
+}
+
+/// A successful authorization response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CallbackResult {
+    pub code: String,
+    pub state: Option<String>,
+}
+
+/// Extracts the authorization response from the full redirect URL,
+/// e.g. one received by a loopback server or pasted by the user.
+pub fn parse_callback(url: &str) -> Result<CallbackResult, CallbackError> {
+    let url = url::Url::parse(url).map_err(|e| CallbackError::InvalidUrl(e.to_string()))?;
+    let query = CallbackQuery::parse(url.query().unwrap_or_default());
+    let state = query.state.clone();
+    let code = query.into_code()?;
+    Ok(CallbackResult { code, state })
+}
+
+/// https://openid.net/specs/openid-connect-core-1_0.html#AuthError
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum CallbackError {
+    #[error("access_denied")]
+    AccessDenied,
+    #[error("login_required")]
+    LoginRequired,
+    #[error("interaction_required")]
+    InteractionRequired,
+    #[error("consent_required")]
+    ConsentRequired,
+    #[error("account_selection_required")]
+    AccountSelectionRequired,
+    #[error("{0}")]
+    Other(String),
+    /// neither `code` nor `error` was given
+    #[error("callback has no code")]
+    MissingCode,
+    /// `state` is missing or differs from the one sent in the authorization request
+    #[error("state does not match")]
+    StateMismatch,
+    #[error("invalid callback URL: {0}")]
+    InvalidUrl(String),
+}
+
+impl CallbackError {
+    /// Whether a silent (`prompt=none`) request failed only because the user must interact,
+    /// so that retrying with an interactive prompt may succeed.
+    pub fn is_interaction_required(&self) -> bool {
+        matches!(
+            self,
+            Self::LoginRequired
+                | Self::InteractionRequired
+                | Self::ConsentRequired
+                | Self::AccountSelectionRequired
+        )
+    }
+}
+
+impl FromStr for CallbackError {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let e = match s {
+            "access_denied" => Self::AccessDenied,
+            "login_required" => Self::LoginRequired,
+            "interaction_required" => Self::InteractionRequired,
+            "consent_required" => Self::ConsentRequired,
+            "account_selection_required" => Self::AccountSelectionRequired,
+            _ => Self::Other(s.to_string()),
+        };
+        Ok(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_callback_query_code() {
+        let query = CallbackQuery::parse("state=xyz&code=4%2F0Adeu5B&scope=openid");
+        assert_eq!(query.state.as_deref(), Some("xyz"));
+        assert_eq!(query.into_code(), Ok("4/0Adeu5B".to_string()));
+    }
+
+    #[test]
+    fn test_callback_query_error() {
+        let cases = [
+            ("error=access_denied&state=xyz", CallbackError::AccessDenied),
+            ("error=login_required", CallbackError::LoginRequired),
+            (
+                "error=interaction_required",
+                CallbackError::InteractionRequired,
+            ),
+            ("error=consent_required", CallbackError::ConsentRequired),
+            (
+                "error=temporarily_unavailable",
+                CallbackError::Other("temporarily_unavailable".to_string()),
+            ),
+            ("state=xyz", CallbackError::MissingCode),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(CallbackQuery::parse(query).into_code(), Err(expected));
+        }
+        assert!(CallbackError::LoginRequired.is_interaction_required());
+        assert!(!CallbackError::AccessDenied.is_interaction_required());
+    }
+
+    #[test]
+    fn test_parse_callback() {
+        let result = parse_callback("http://localhost:8080/oauth2/callback?state=xyz&code=4%2F0Ad");
+        let expected = CallbackResult {
+            code: "4/0Ad".to_string(),
+            state: Some("xyz".to_string()),
+        };
+        assert_eq!(result, Ok(expected));
+        let result = parse_callback("http://localhost:8080/oauth2/callback?error=access_denied");
+        assert_eq!(result, Err(CallbackError::AccessDenied));
+        let result = parse_callback("http://localhost:8080/oauth2/callback?state=xyz");
+        assert_eq!(result, Err(CallbackError::MissingCode));
+        let result = parse_callback("/oauth2/callback?code=abc");
+        assert!(
+            matches!(result, Err(CallbackError::InvalidUrl(_))),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_healthz() {
+        use tower::ServiceExt;
+
+        let request = http::Request::get("/healthz")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = make_router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "application/json"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let expected = serde_json::json!({
+            "status": "ok",
+            "version": env!("CARGO_PKG_VERSION"),
+        });
+        assert_eq!(body, expected);
+    }
+
+    #[tokio::test]
+    async fn test_authorized_client_extractor() {
+        use tower::ServiceExt;
+
+        #[derive(Clone)]
+        struct AppState {
+            sessions: SessionStore,
+            secret: WebClientSecret,
+        }
+
+        impl FromRef<AppState> for SessionStore {
+            fn from_ref(state: &AppState) -> Self {
+                state.sessions.clone()
+            }
+        }
+
+        impl FromRef<AppState> for WebClientSecret {
+            fn from_ref(state: &AppState) -> Self {
+                state.secret.clone()
+            }
+        }
+
+        let sessions = SessionStore::default();
+        let token = crate::test_util::token(&crate::scope::Calendar).build();
+        sessions.insert("s1", token).await;
+        let state = AppState {
+            sessions,
+            secret: crate::test_util::secret(),
+        };
+        let router = Router::new()
+            .route(
+                "/me",
+                routing::get(|client: AuthorizedClient| async move {
+                    client.token().access_token().to_string()
+                }),
+            )
+            .with_state(state);
+
+        let cases = [
+            (Some("theme=dark; session=s1"), http::StatusCode::OK),
+            (Some("session=unknown"), http::StatusCode::UNAUTHORIZED),
+            (None, http::StatusCode::UNAUTHORIZED),
+        ];
+        for (cookie, expected) in cases {
+            let mut request = http::Request::get("/me");
+            if let Some(cookie) = cookie {
+                request = request.header(http::header::COOKIE, cookie);
+            }
+            let request = request.body(axum::body::Body::empty()).unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), expected, "{cookie:?}");
+            if expected == http::StatusCode::OK {
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                assert_eq!(&body[..], b"access");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_store_ttl() {
+        let token = Token::builder().access_token("access").build();
+        let sessions = SessionStore::new().with_cookie("sid");
+        assert_eq!(sessions.cookie(), "sid");
+        sessions.insert("s1", token.clone()).await;
+        assert_eq!(sessions.get("s1").await, Some(token.clone()));
+        assert_eq!(sessions.evict_expired().await, 0);
+
+        let sessions = sessions.with_ttl(Duration::ZERO);
+        sessions.insert("s2", token).await;
+        assert_eq!(sessions.get("s2").await, None);
+        assert_eq!(sessions.evict_expired().await, 2);
+        assert_eq!(sessions.remove("s1").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_callback_state() {
+        let (code_tx, mut code_rx) = mpsc::unbounded_channel();
+        let router = make_callback_router("/oauth2/callback", CallbackState::new("xyz", code_tx));
+        let addr = crate::test_util::serve(router).await;
+        let url = format!("http://{addr}/oauth2/callback");
+
+        let cases = [
+            ("code=abc&state=wrong", http::StatusCode::BAD_REQUEST),
+            ("code=abc", http::StatusCode::BAD_REQUEST),
+            ("code=abc&state=xyz", http::StatusCode::OK),
+        ];
+        for (query, expected) in cases {
+            let res = reqwest::get(format!("{url}?{query}")).await.unwrap();
+            assert_eq!(res.status(), expected, "{query}");
+        }
+        assert_eq!(code_rx.recv().await.as_deref(), Some("abc"));
+        assert!(code_rx.try_recv().is_err());
+    }
 }
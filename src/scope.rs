@@ -4,7 +4,7 @@ use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
-use std::sync::LazyLock;
+use std::sync::{LazyLock, Mutex};
 
 mod serde;
 
@@ -28,6 +28,17 @@ pub trait SingleScope: private::Sealed + fmt::Debug + Send + Sync + 'static {
     fn equals(&self, other: &dyn SingleScope) -> bool;
 
     fn hash_value(&self) -> u64;
+
+    /// Narrower scopes granted along with this one, e.g. `calendar` implies `calendar.readonly`.
+    fn implies(&self) -> &'static [DynSingleScope] {
+        &[]
+    }
+}
+
+/// Whether `s` itself or one of its implied scopes is `other`.
+fn single_grants(s: &dyn SingleScope, other: &dyn SingleScope) -> bool {
+    let other = other.as_str();
+    s.as_str() == other || s.implies().iter().any(|i| i.as_str() == other)
 }
 
 pub trait Scope: private::Sealed + Send + Sync + 'static {
@@ -36,12 +47,43 @@ pub trait Scope: private::Sealed + Send + Sync + 'static {
     fn scope_str(&self) -> HashSet<&'static str>;
 
     fn grants(&self, other: &dyn SingleScope) -> bool {
-        let other = other.as_dyn();
-        self.scope().contains(&other)
+        self.scope().iter().any(|s| single_grants(s, other))
+    }
+
+    /// [`Scope::scope`] together with every implied scope.
+    fn expanded(&self) -> HashSet<DynSingleScope> {
+        let mut scope = self.scope();
+        let implied: Vec<_> = scope.iter().flat_map(|s| s.implies()).copied().collect();
+        scope.extend(implied);
+        scope
     }
 
     fn boxed_clone(&self) -> BoxScope;
 
+    fn intersection(&self, other: &dyn Scope) -> HashSet<DynSingleScope> {
+        self.scope()
+            .into_iter()
+            .filter(|s| other.grants(s))
+            .collect()
+    }
+
+    fn difference(&self, other: &dyn Scope) -> HashSet<DynSingleScope> {
+        self.scope()
+            .into_iter()
+            .filter(|s| !other.grants(s))
+            .collect()
+    }
+
+    /// whether all of `self`'s scopes are granted by `other`
+    fn is_subset(&self, other: &dyn Scope) -> bool {
+        self.scope().iter().all(|s| other.grants(s))
+    }
+
+    /// whether `self` grants all of `other`'s scopes
+    fn is_superset(&self, other: &dyn Scope) -> bool {
+        other.scope().iter().all(|s| self.grants(s))
+    }
+
     fn space_delimited(&self) -> SpaceDelimitedScope {
         self.scope().into_iter().collect::<Vec<_>>().into()
     }
@@ -72,6 +114,26 @@ impl PartialEq for DynSingleScope {
 
 impl Eq for DynSingleScope {}
 
+impl PartialOrd for DynSingleScope {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Ordered by [`SingleScope::as_str`]. Distinct scopes sharing a string,
+/// such as a [`CustomScope`] spelling out a known one, are told apart by their type.
+impl Ord for DynSingleScope {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str()).then_with(|| {
+            if self == other {
+                std::cmp::Ordering::Equal
+            } else {
+                Any::type_id(self.0.as_any()).cmp(&Any::type_id(other.0.as_any()))
+            }
+        })
+    }
+}
+
 impl Hash for DynSingleScope {
     fn hash<H: Hasher>(&self, state: &mut H) {
         let v = self.0.hash_value();
@@ -135,6 +197,11 @@ impl SingleScope for DynSingleScope {
     fn hash_value(&self) -> u64 {
         self.0.hash_value()
     }
+
+    #[inline]
+    fn implies(&self) -> &'static [DynSingleScope] {
+        self.0.implies()
+    }
 }
 
 impl Scope for DynSingleScope {
@@ -146,6 +213,10 @@ impl Scope for DynSingleScope {
         [self.as_str()].into()
     }
 
+    fn grants(&self, other: &dyn SingleScope) -> bool {
+        single_grants(self, other)
+    }
+
     fn boxed_clone(&self) -> BoxScope {
         box_scope!(*self)
     }
@@ -187,9 +258,179 @@ impl Scope for NoScope {
     }
 }
 
+/// A scope not enumerated by this crate, identified by its string.
+///
+/// [`SingleScope::as_str`] hands out `&'static str`, so each distinct string is
+/// interned and leaked for the rest of the process, up to
+/// [`CustomScope::MAX_INTERNED`] strings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomScope(String);
+
+type CustomScopes = HashMap<String, &'static CustomScope>;
+
+static CUSTOM_SCOPES: LazyLock<Mutex<CustomScopes>> = LazyLock::new(Default::default);
+
+/// Returns the instance interned for `s`, leaking a new one if `scopes` has room.
+fn intern(scopes: &mut CustomScopes, s: String, max: usize) -> Option<&'static CustomScope> {
+    if let Some(scope) = scopes.get(&s) {
+        return Some(scope);
+    }
+    if scopes.len() >= max {
+        return None;
+    }
+    let scope: &'static CustomScope = Box::leak(Box::new(CustomScope(s.clone())));
+    scopes.insert(s, scope);
+    Some(scope)
+}
+
+impl CustomScope {
+    /// How many distinct strings are leaked at most.
+    pub const MAX_INTERNED: usize = 1024;
+
+    /// # Panics
+    ///
+    /// Panics if [`CustomScope::MAX_INTERNED`] other strings are already interned;
+    /// see [`CustomScope::try_new`].
+    pub fn new<S: Into<String>>(s: S) -> Self {
+        Self::try_new(s).expect("too many distinct custom scopes")
+    }
+
+    /// Interns `s`, or returns `None` if [`CustomScope::MAX_INTERNED`] other strings
+    /// are already interned.
+    pub fn try_new<S: Into<String>>(s: S) -> Option<Self> {
+        let mut scopes = CUSTOM_SCOPES.lock().unwrap_or_else(|e| e.into_inner());
+        intern(&mut scopes, s.into(), Self::MAX_INTERNED).cloned()
+    }
+
+    /// Returns the `'static` instance shared by every `CustomScope` with the same string.
+    fn interned(&self) -> &'static CustomScope {
+        let scopes = CUSTOM_SCOPES.lock().unwrap_or_else(|e| e.into_inner());
+        // every instance is created through `try_new`
+        scopes[&self.0]
+    }
+}
+
+impl fmt::Display for CustomScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl private::Sealed for CustomScope {}
+
+impl SingleScope for CustomScope {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_dyn(&self) -> DynSingleScope {
+        DynSingleScope(self.interned())
+    }
+
+    fn as_str(&self) -> &'static str {
+        &self.interned().0
+    }
+
+    fn equals(&self, other: &dyn SingleScope) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .is_some_and(|o| o.0 == self.0)
+    }
+
+    fn hash_value(&self) -> u64 {
+        let mut hasher = ::std::hash::DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Scope for CustomScope {
+    fn scope(&self) -> HashSet<DynSingleScope> {
+        [self.as_dyn()].into()
+    }
+
+    fn scope_str(&self) -> HashSet<&'static str> {
+        [self.as_str()].into()
+    }
+
+    fn grants(&self, other: &dyn SingleScope) -> bool {
+        self.0 == other.as_str()
+    }
+
+    fn boxed_clone(&self) -> BoxScope {
+        box_scope!(self.clone())
+    }
+
+    fn space_delimited(&self) -> SpaceDelimitedScope {
+        vec![self.as_dyn()].into()
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct SpaceDelimitedScope(Vec<DynSingleScope>);
 
+impl SpaceDelimitedScope {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, DynSingleScope> {
+        self.0.iter()
+    }
+
+    /// The scope strings in order, e.g. for a JSON array.
+    pub fn to_vec_strs(&self) -> Vec<&'static str> {
+        self.0.iter().map(|s| s.as_str()).collect()
+    }
+
+    /// Joins the scope strings with `sep` instead of the space [`fmt::Display`] uses.
+    pub fn join(&self, sep: &str) -> String {
+        self.to_vec_strs().join(sep)
+    }
+
+    /// Returns a copy ordered lexicographically by [`SingleScope::as_str`].
+    pub fn sorted(&self) -> Self {
+        let mut inner = self.0.clone();
+        inner.sort_by_key(|s| s.as_str());
+        Self(inner)
+    }
+
+    /// Returns a copy with `scope` filtered out, preserving the order of the rest.
+    pub fn without(&self, scope: &dyn SingleScope) -> Self {
+        let inner = self.0.iter().filter(|s| !s.equals(scope)).copied();
+        Self(inner.collect())
+    }
+
+    /// Like [`FromStr`], but unknown scopes become [`CustomScope`] instead of an error.
+    /// Unknown scopes past [`CustomScope::MAX_INTERNED`] are dropped.
+    pub fn parse_lenient(s: &str) -> Self {
+        let inner = s
+            .split(' ')
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| match s.parse() {
+                Ok(scope) => Some(scope),
+                Err(_) => {
+                    let scope = CustomScope::try_new(s).map(|c| c.as_dyn());
+                    if scope.is_none() {
+                        tracing::warn!(scope = s, "dropping scope past the custom scope limit");
+                    }
+                    scope
+                }
+            })
+            .collect::<Vec<DynSingleScope>>();
+        inner.into()
+    }
+}
+
 impl AsRef<[DynSingleScope]> for SpaceDelimitedScope {
     fn as_ref(&self) -> &[DynSingleScope] {
         &self.0
@@ -208,9 +449,9 @@ impl FromStr for SpaceDelimitedScope {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let inner = s
             .split(' ')
-            .map(|s| s.parse())
-            .collect::<Result<Vec<DynSingleScope>, _>>()
-            .map_err(|e| e.to_string())?;
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().map_err(|e| format!("{e}: {s}")))
+            .collect::<Result<Vec<DynSingleScope>, _>>()?;
         Ok(inner.into())
     }
 }
@@ -228,12 +469,43 @@ impl fmt::Display for SpaceDelimitedScope {
     }
 }
 
+impl IntoIterator for SpaceDelimitedScope {
+    type Item = DynSingleScope;
+    type IntoIter = std::vec::IntoIter<DynSingleScope>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SpaceDelimitedScope {
+    type Item = &'a DynSingleScope;
+    type IntoIter = std::slice::Iter<'a, DynSingleScope>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
 impl From<Vec<DynSingleScope>> for SpaceDelimitedScope {
     fn from(value: Vec<DynSingleScope>) -> Self {
         Self(value)
     }
 }
 
+impl FromIterator<DynSingleScope> for SpaceDelimitedScope {
+    fn from_iter<I: IntoIterator<Item = DynSingleScope>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// Appends in order, keeping duplicates like [`Vec`] does.
+impl Extend<DynSingleScope> for SpaceDelimitedScope {
+    fn extend<I: IntoIterator<Item = DynSingleScope>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
 impl private::Sealed for SpaceDelimitedScope {}
 
 impl Scope for SpaceDelimitedScope {
@@ -295,6 +567,31 @@ impl Clone for BoxScope {
     }
 }
 
+impl FromStr for BoxScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let scope: SpaceDelimitedScope = s.parse()?;
+        Ok(scope.into_boxed())
+    }
+}
+
+impl PartialEq for BoxScope {
+    fn eq(&self, other: &Self) -> bool {
+        self.scope() == other.scope()
+    }
+}
+
+impl Eq for BoxScope {}
+
+impl Hash for BoxScope {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut values: Vec<u64> = self.scope().iter().map(|s| s.hash_value()).collect();
+        values.sort_unstable();
+        values.hash(state);
+    }
+}
+
 impl fmt::Debug for BoxScope {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("BoxScope").field(&self.scope_str()).finish()
@@ -342,20 +639,33 @@ where
 }
 
 macro_rules! scope {
+    (@str [$s:literal] $($t:tt)*) => { $s };
+    (@str [] $i0:ident $(. $i:ident)*) => {
+        concat!(
+            "https://www.googleapis.com/auth/",
+            stringify!($i0)
+            $(, ".", stringify!($i))*
+        )
+    };
     { $(
         $( #[$m:meta] )*
-        $i0:ident $(. $i:ident)* ;
+        $i0:ident $(. $i:ident)* $(= $s:literal)?
+        $(=> [ $( $j0:ident $(. $j:ident)* ),* ])? ;
     )+ } => { ::paste::paste! { $(
         $( #[$m:meta] )*
         #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
         pub struct [< $i0:camel $( $i:camel )* >];
 
+        #[doc = concat!("[`", stringify!([< $i0:camel $( $i:camel )* >]), "`] as a [`DynSingleScope`].")]
+        pub const [< $i0:snake:upper $( _ $i:snake:upper )* >]: DynSingleScope =
+            DynSingleScope(& [< $i0:camel $( $i:camel )* >]);
+
         impl [< $i0:camel $( $i:camel )* >] {
-            pub const STR: &'static str = concat!(
-                "https://www.googleapis.com/auth/",
-                stringify!($i0)
-                $(, ".", stringify!($i))*
-            );
+            pub const STR: &'static str = scope!(@str [$($s)?] $i0 $(. $i)*);
+
+            const IMPLIES: &'static [DynSingleScope] = &[ $($(
+                DynSingleScope(& [< $j0:camel $( $j:camel )* >])
+            ),*)? ];
 
             pub const fn new() -> Self {
                 Self
@@ -407,6 +717,11 @@ macro_rules! scope {
                 self.hash(&mut hasher);
                 hasher.finish()
             }
+
+            #[inline]
+            fn implies(&self) -> &'static [DynSingleScope] {
+                Self::IMPLIES
+            }
         }
 
         impl Scope for [< $i0:camel $( $i:camel )* >] {
@@ -419,7 +734,7 @@ macro_rules! scope {
             }
 
             fn grants(&self, other: &dyn SingleScope) -> bool {
-                Self::STR == other.as_str()
+                single_grants(self, other)
             }
 
             fn boxed_clone(&self) -> BoxScope {
@@ -435,14 +750,48 @@ macro_rules! scope {
 
 // https://developers.google.com/identity/protocols/oauth2/scopes#calendar
 scope! {
-    calendar;
-    calendar.readonly;
-    calendar.events;
+    calendar => [
+        calendar.readonly,
+        calendar.events,
+        calendar.events.readonly,
+        calendar.settings.readonly
+    ];
+    calendar.readonly => [calendar.events.readonly, calendar.settings.readonly];
+    calendar.events => [calendar.events.readonly];
     calendar.events.readonly;
     calendar.settings.readonly;
     calendar.addons.execute;
 }
 
+// https://developers.google.com/identity/protocols/oauth2/scopes#oauth2
+scope! {
+    openid = "openid";
+    userinfo.email;
+    userinfo.profile;
+}
+
+// https://developers.google.com/identity/protocols/oauth2/scopes#drive
+scope! {
+    drive => [
+        drive.readonly,
+        drive.file,
+        drive.metadata,
+        drive.metadata.readonly,
+        drive.appdata
+    ];
+    drive.readonly => [drive.metadata.readonly];
+    drive.file;
+    drive.metadata => [drive.metadata.readonly];
+    drive.metadata.readonly;
+    drive.appdata;
+}
+
+// segments with a hyphen are spelled out, since they cannot be written as idents
+scope! {
+    cloud_platform = "https://www.googleapis.com/auth/cloud-platform" => [cloud_platform.read_only];
+    cloud_platform.read_only = "https://www.googleapis.com/auth/cloud-platform.read-only";
+}
+
 macro_rules! apply_all_scope {
     ($m:ident) => {
         $m! {
@@ -451,7 +800,18 @@ macro_rules! apply_all_scope {
             calendar.events,
             calendar.events.readonly,
             calendar.settings.readonly,
-            calendar.addons.execute
+            calendar.addons.execute,
+            openid,
+            userinfo.email,
+            userinfo.profile,
+            drive,
+            drive.readonly,
+            drive.file,
+            drive.metadata,
+            drive.metadata.readonly,
+            drive.appdata,
+            cloud_platform,
+            cloud_platform.read_only
         }
     };
 }
@@ -475,6 +835,16 @@ fn all_scope_map() -> HashMap<&'static str, DynSingleScope> {
 pub static ALL_SCOPE_MAP: LazyLock<HashMap<&'static str, DynSingleScope>> =
     LazyLock::new(all_scope_map);
 
+/// Every scope known to this crate, in declaration order.
+pub fn all_scopes() -> impl Iterator<Item = DynSingleScope> {
+    ALL_SCOPE_PAIRS.iter().map(|(_, s)| *s)
+}
+
+/// Strings of [`all_scopes`].
+pub fn all_scope_strs() -> impl Iterator<Item = &'static str> {
+    ALL_SCOPE_PAIRS.iter().map(|(s, _)| *s)
+}
+
 /// ```
 /// let combined = google_oauth::combine_scope![calendar, calendar.readonly];
 /// # let _ = combined;
@@ -493,6 +863,87 @@ macro_rules! combine_scope {
     } } };
 }
 
+/// [`combine_scope!`] erased into a [`BoxScope`], for struct fields and collections.
+///
+/// ```
+/// use google_oauth::scope::BoxScope;
+///
+/// struct Config {
+///     scope: BoxScope,
+/// }
+///
+/// let config = Config {
+///     scope: google_oauth::boxed_scope![calendar, calendar.readonly],
+/// };
+/// # let _ = config.scope;
+/// ```
+#[macro_export]
+macro_rules! boxed_scope {
+    [ $($t:tt)* ] => { {
+        use $crate::scope::Scope;
+        $crate::combine_scope![ $($t)* ].into_boxed()
+    } };
+}
+
+/// Checks that a granted scope covers the listed scopes, failing with an
+/// [`InsufficientScopeError`](crate::InsufficientScopeError) naming them.
+/// `[..]` needs all of them, `any [..]` one of them.
+/// Implied scopes count, so `calendar` satisfies `calendar.readonly`.
+///
+/// ```
+/// use google_oauth::scope::{CalendarReadonly, Scope};
+/// use google_oauth::{AuthorizedClient, InsufficientScopeError, Token, WebClientSecret};
+///
+/// /// https://developers.google.com/calendar/api/v3/reference/freebusy/query
+/// fn free_busy(
+///     client: &AuthorizedClient,
+/// ) -> Result<reqwest::RequestBuilder, InsufficientScopeError> {
+///     let scope = client.token().scope();
+///     google_oauth::require_scopes!(any [calendar, calendar.readonly] in scope)?;
+///     Ok(client.post("/calendar/v3/freeBusy"))
+/// }
+///
+/// let secret = WebClientSecret::builder()
+///     .client_id("client_id")
+///     .client_secret("client_secret")
+///     .build()
+///     .unwrap();
+/// let token = Token::builder().scope(CalendarReadonly.space_delimited()).build();
+/// let client = AuthorizedClient::new(secret.clone(), token);
+/// assert!(free_busy(&client).is_ok());
+///
+/// let client = AuthorizedClient::new(secret, Token::builder().build());
+/// let err = free_busy(&client).unwrap_err();
+/// assert!(err.to_string().starts_with("insufficient scope: need one of"));
+/// ```
+#[macro_export]
+macro_rules! require_scopes {
+    ( [
+        $( $i0:ident $(. $i:ident)* ),+ $(,)?
+    ] in $s:expr ) => { $crate::__private::paste! { {
+        use $crate::scope::Scope;
+        let scope: &$crate::scope::SpaceDelimitedScope = $s;
+        if $( Scope::grants(scope, & $crate::scope::[< $i0:camel $($i:camel)* >]) )&&+ {
+            Ok(())
+        } else {
+            let required = vec![ $( $crate::scope::[< $i0:camel $($i:camel)* >]::STR ),+ ];
+            Err($crate::InsufficientScopeError::all(required, scope.clone()))
+        }
+    } } };
+    ( any [
+        $( $i0:ident $(. $i:ident)* ),+ $(,)?
+    ] in $s:expr ) => { $crate::__private::paste! { {
+        use $crate::scope::Scope;
+        let scope: &$crate::scope::SpaceDelimitedScope = $s;
+        if $( Scope::grants(scope, & $crate::scope::[< $i0:camel $($i:camel)* >]) )||+ {
+            Ok(())
+        } else {
+            let required = vec![ $( $crate::scope::[< $i0:camel $($i:camel)* >]::STR ),+ ];
+            Err($crate::InsufficientScopeError::any(required, scope.clone()))
+        }
+    } } };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -504,6 +955,40 @@ mod tests {
         assert_eq!(ser, payload);
     }
 
+    #[test]
+    fn test_cloud_platform() {
+        let payload = r#""https://www.googleapis.com/auth/cloud-platform""#;
+        let de: CloudPlatform = serde_json::from_str(payload).unwrap();
+        assert_eq!(serde_json::to_string(&de).unwrap(), payload);
+        let s: DynSingleScope = "https://www.googleapis.com/auth/cloud-platform"
+            .parse()
+            .unwrap();
+        assert_eq!(s, CloudPlatform.as_dyn());
+        let s: DynSingleScope = "https://www.googleapis.com/auth/cloud-platform.read-only"
+            .parse()
+            .unwrap();
+        assert_eq!(s, CloudPlatformReadOnly.as_dyn());
+        assert!(CloudPlatform.grants(&CloudPlatformReadOnly));
+        assert!(!CloudPlatformReadOnly.grants(&CloudPlatform));
+    }
+
+    #[test]
+    fn test_scope_consts() {
+        let scope = SpaceDelimitedScope::from(vec![CALENDAR, CALENDAR_EVENTS_READONLY, OPENID]);
+        assert_eq!(
+            scope.to_string(),
+            format!(
+                "{} {} {}",
+                Calendar::STR,
+                CalendarEventsReadonly::STR,
+                Openid::STR
+            )
+        );
+        assert_eq!(USERINFO_EMAIL, UserinfoEmail.as_dyn());
+        assert_eq!(CLOUD_PLATFORM_READ_ONLY, CloudPlatformReadOnly.as_dyn());
+        assert!(CALENDAR.grants(&CalendarEventsReadonly));
+    }
+
     #[test]
     fn test_calendar_de() {
         let payload = format!(r#""{}""#, Calendar::STR);
@@ -559,4 +1044,268 @@ mod tests {
         let de: SpaceDelimitedScope = serde_json::from_str(&payload).unwrap();
         assert_eq!(de, scope);
     }
+
+    #[test]
+    fn test_space_delimited_scope_bincode() {
+        let scope: SpaceDelimitedScope = vec![Calendar.as_dyn(), CalendarEvents.as_dyn()].into();
+        let bytes = bincode::serialize(&scope).unwrap();
+        assert_eq!(
+            bincode::deserialize::<SpaceDelimitedScope>(&bytes).unwrap(),
+            scope
+        );
+
+        let empty = SpaceDelimitedScope::default();
+        let json = serde_json::to_string(&empty).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SpaceDelimitedScope>(&json).unwrap(),
+            empty
+        );
+        let bytes = bincode::serialize(&empty).unwrap();
+        assert_eq!(
+            bincode::deserialize::<SpaceDelimitedScope>(&bytes).unwrap(),
+            empty
+        );
+    }
+
+    #[test]
+    fn test_space_delimited_scope_de_seq() {
+        let string = serde_json::json!(format!("{} {}", Calendar::STR, CalendarEvents::STR));
+        let array = serde_json::json!([Calendar::STR, CalendarEvents::STR]);
+        let from_string: SpaceDelimitedScope = serde_json::from_value(string).unwrap();
+        let from_array: SpaceDelimitedScope = serde_json::from_value(array).unwrap();
+        assert_eq!(from_string, from_array);
+        assert_eq!(from_array.len(), 2);
+        let unknown = serde_json::json!([Calendar::STR, "https://example.com/unknown"]);
+        assert!(serde_json::from_value::<SpaceDelimitedScope>(unknown).is_err());
+    }
+
+    #[test]
+    fn test_scope_algebra() {
+        let a = Calendar.with(CalendarReadonly).with(CalendarEvents);
+        let b = CalendarReadonly.with(CalendarEventsReadonly);
+        assert_eq!(a.intersection(&b), [CalendarReadonly.as_dyn()].into());
+        assert_eq!(
+            a.difference(&b),
+            [Calendar.as_dyn(), CalendarEvents.as_dyn()].into()
+        );
+        // `calendar` implies `calendar.events.readonly`
+        assert!(b.difference(&a).is_empty());
+        assert!(CalendarReadonly.is_subset(&a));
+        assert!(a.is_superset(&CalendarReadonly));
+        assert!(!a.is_subset(&b));
+        assert!(!b.is_superset(&a));
+    }
+
+    #[test]
+    fn test_scope_algebra_no_scope() {
+        let a = Calendar.with(CalendarReadonly);
+        assert!(a.intersection(&NoScope).is_empty());
+        assert_eq!(a.difference(&NoScope), a.scope());
+        assert!(NoScope.difference(&a).is_empty());
+        assert!(NoScope.is_subset(&a));
+        assert!(NoScope.is_subset(&NoScope));
+        assert!(!a.is_subset(&NoScope));
+        assert!(a.is_superset(&NoScope));
+    }
+
+    #[test]
+    fn test_scope_implies() {
+        assert!(Calendar.grants(&CalendarReadonly));
+        assert!(Calendar.grants(&CalendarEventsReadonly));
+        assert!(!CalendarReadonly.grants(&Calendar));
+        assert!(!CalendarEventsReadonly.grants(&CalendarEvents));
+        assert!(Calendar.as_dyn().grants(&CalendarReadonly));
+        assert!(Calendar.space_delimited().grants(&CalendarReadonly));
+        assert!(Calendar.grants(&CustomScope::new(CalendarReadonly::STR)));
+        assert!(!CalendarAddonsExecute.grants(&CalendarReadonly));
+        assert!(CalendarReadonly.is_subset(&Calendar));
+        assert!(!Calendar.is_subset(&CalendarReadonly));
+
+        let expanded = CalendarEvents.expanded();
+        assert_eq!(
+            expanded,
+            [CalendarEvents.as_dyn(), CalendarEventsReadonly.as_dyn()].into()
+        );
+        assert!(!Calendar.scope().contains(&CalendarReadonly.as_dyn()));
+        assert!(Calendar.expanded().contains(&CalendarReadonly.as_dyn()));
+    }
+
+    #[test]
+    fn test_all_scopes() {
+        assert_eq!(all_scopes().count(), ALL_SCOPE_PAIRS.len());
+        assert_eq!(all_scope_strs().count(), ALL_SCOPE_PAIRS.len());
+        assert!(all_scopes().any(|s| s == Calendar.as_dyn()));
+        assert!(all_scope_strs().any(|s| s == Calendar::STR));
+    }
+
+    #[test]
+    fn test_box_scope_eq() {
+        let a = crate::combine_scope![calendar, calendar.readonly].into_boxed();
+        let b = crate::combine_scope![calendar.readonly, calendar].into_boxed();
+        let c = crate::combine_scope![calendar, calendar.events].into_boxed();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        let set: HashSet<BoxScope> = [a, b, c].into();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_box_scope_from_str() {
+        let payload = format!("{} {}", Calendar::STR, CalendarEvents::STR);
+        let scope: BoxScope = payload.parse().unwrap();
+        assert_eq!(scope, Calendar.with(CalendarEvents).into_boxed());
+    }
+
+    #[test]
+    fn test_box_scope_from_str_unknown() {
+        let unknown = "https://www.googleapis.com/auth/unknown";
+        let payload = format!("{} {unknown} {}", Calendar::STR, CalendarEvents::STR);
+        let err = payload.parse::<BoxScope>().unwrap_err();
+        assert!(err.contains(unknown), "{err}");
+    }
+
+    #[test]
+    fn test_custom_scope() {
+        let fitness = "https://www.googleapis.com/auth/fitness.activity.read";
+        let a = CustomScope::new(fitness);
+        let b = CustomScope::new(fitness);
+        assert!(a.equals(&b));
+        assert_eq!(a.as_dyn(), b.as_dyn());
+        assert_eq!(a.as_str(), fitness);
+        assert!(!a.equals(&Calendar));
+        assert!(!a.as_dyn().equals(&CustomScope::new(Calendar::STR)));
+        assert_eq!(CustomScope::try_new(fitness), Some(a));
+    }
+
+    #[test]
+    fn test_custom_scope_intern_limit() {
+        let mut scopes = CustomScopes::new();
+        let first = intern(&mut scopes, "first".to_string(), 1).unwrap();
+        assert_eq!(first.0, "first");
+        let again = intern(&mut scopes, "first".to_string(), 1).unwrap();
+        assert!(std::ptr::eq(first, again));
+        assert!(intern(&mut scopes, "second".to_string(), 1).is_none());
+        assert_eq!(scopes.len(), 1);
+    }
+
+    #[test]
+    fn test_space_delimited_scope_parse_lenient() {
+        let fitness = "https://www.googleapis.com/auth/fitness.activity.read";
+        let payload = format!("{} {fitness}", Calendar::STR);
+        assert!(payload.parse::<SpaceDelimitedScope>().is_err());
+        let scope = SpaceDelimitedScope::parse_lenient(&payload);
+        let expected: SpaceDelimitedScope =
+            vec![Calendar.as_dyn(), CustomScope::new(fitness).as_dyn()].into();
+        assert_eq!(scope, expected);
+        assert!(scope.grants(&Calendar));
+        assert!(scope.grants(&CustomScope::new(fitness)));
+        assert_eq!(scope.to_string(), payload);
+    }
+
+    #[test]
+    fn test_space_delimited_scope_iter() {
+        let scope: SpaceDelimitedScope = vec![
+            Calendar.as_dyn(),
+            CalendarReadonly.as_dyn(),
+            CalendarEvents.as_dyn(),
+        ]
+        .into();
+        assert_eq!(scope.len(), 3);
+        assert!(!scope.is_empty());
+        assert!(SpaceDelimitedScope::default().is_empty());
+        let expected = vec![Calendar::STR, CalendarReadonly::STR, CalendarEvents::STR];
+        let strs: Vec<&'static str> = scope.iter().map(SingleScope::as_str).collect();
+        assert_eq!(strs, expected);
+        let strs: Vec<&'static str> = (&scope).into_iter().map(|s| s.as_str()).collect();
+        assert_eq!(strs, expected);
+        let strs: Vec<&'static str> = scope.into_iter().map(|s| s.as_str()).collect();
+        assert_eq!(strs, expected);
+    }
+
+    #[test]
+    fn test_space_delimited_scope_collect() {
+        let mut scope: SpaceDelimitedScope = [
+            Calendar.as_dyn(),
+            CalendarReadonly.as_dyn(),
+            CalendarEvents.as_dyn(),
+        ]
+        .into_iter()
+        .collect();
+        let expected = [Calendar::STR, CalendarReadonly::STR, CalendarEvents::STR];
+        assert_eq!(scope.to_string(), expected.join(" "));
+        scope.extend([CalendarSettingsReadonly.as_dyn()]);
+        assert_eq!(scope.len(), 4);
+        assert!(scope
+            .to_string()
+            .ends_with(&format!(" {}", CalendarSettingsReadonly::STR)));
+    }
+
+    #[test]
+    fn test_space_delimited_scope_join() {
+        let scope: SpaceDelimitedScope = [Calendar.as_dyn(), CalendarEvents.as_dyn()]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            scope.join(", "),
+            format!("{}, {}", Calendar::STR, CalendarEvents::STR)
+        );
+        assert_eq!(scope.join(" "), scope.to_string());
+        assert_eq!(scope.to_vec_strs(), [Calendar::STR, CalendarEvents::STR]);
+        assert_eq!(SpaceDelimitedScope::default().join(", "), "");
+    }
+
+    #[test]
+    fn test_dyn_single_scope_ord() {
+        use std::collections::BTreeSet;
+
+        let set: BTreeSet<DynSingleScope> = [
+            CalendarReadonly.as_dyn(),
+            Calendar.as_dyn(),
+            CalendarEvents.as_dyn(),
+            Calendar.as_dyn(),
+        ]
+        .into_iter()
+        .collect();
+        let strs: Vec<&str> = set.iter().map(|s| s.as_str()).collect();
+        assert_eq!(
+            strs,
+            [Calendar::STR, CalendarEvents::STR, CalendarReadonly::STR]
+        );
+
+        let custom = CustomScope::new(Calendar::STR).as_dyn();
+        assert_ne!(custom, Calendar.as_dyn());
+        assert_ne!(custom.cmp(&Calendar.as_dyn()), std::cmp::Ordering::Equal);
+        assert_eq!(custom.cmp(&custom), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_space_delimited_scope_without() {
+        let scope: SpaceDelimitedScope = vec![
+            Calendar.as_dyn(),
+            CalendarReadonly.as_dyn(),
+            CalendarEvents.as_dyn(),
+        ]
+        .into();
+        let removed = scope.without(&CalendarReadonly);
+        let expected: SpaceDelimitedScope = vec![Calendar.as_dyn(), CalendarEvents.as_dyn()].into();
+        assert_eq!(removed, expected);
+        assert_eq!(removed.without(&CalendarReadonly), expected);
+    }
+
+    #[test]
+    fn test_space_delimited_scope_sorted() {
+        let scope: SpaceDelimitedScope = vec![
+            CalendarReadonly.as_dyn(),
+            Calendar.as_dyn(),
+            CalendarEvents.as_dyn(),
+        ]
+        .into();
+        let expected: SpaceDelimitedScope = vec![
+            Calendar.as_dyn(),
+            CalendarEvents.as_dyn(),
+            CalendarReadonly.as_dyn(),
+        ]
+        .into();
+        assert_eq!(scope.sorted(), expected);
+    }
 }
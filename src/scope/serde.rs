@@ -24,7 +24,7 @@ impl<'de> de::Deserialize<'de> for DynSingleScope {
 
 struct DynSingleScopeVisitor;
 
-impl<'de> de::Visitor<'de> for DynSingleScopeVisitor {
+impl de::Visitor<'_> for DynSingleScopeVisitor {
     type Value = DynSingleScope;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -53,7 +53,13 @@ impl<'de> de::Deserialize<'de> for SpaceDelimitedScope {
     where
         D: de::Deserializer<'de>,
     {
-        deserializer.deserialize_str(SpaceDelimitedScopeVisitor)
+        // some servers send an array of scopes instead of a string;
+        // only self-describing formats can tell which, others get what `serialize` wrote
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(SpaceDelimitedScopeVisitor)
+        } else {
+            deserializer.deserialize_str(SpaceDelimitedScopeVisitor)
+        }
     }
 }
 
@@ -63,7 +69,7 @@ impl<'de> de::Visitor<'de> for SpaceDelimitedScopeVisitor {
     type Value = SpaceDelimitedScope;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("a str of space-delimited scope")
+        formatter.write_str("a str of space-delimited scope or a sequence of scopes")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -72,6 +78,17 @@ impl<'de> de::Visitor<'de> for SpaceDelimitedScopeVisitor {
     {
         v.parse().map_err(E::custom)
     }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut scope = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(s) = seq.next_element::<DynSingleScope>()? {
+            scope.push(s);
+        }
+        Ok(scope.into())
+    }
 }
 
 macro_rules! serde_for_scope {
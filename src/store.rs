@@ -0,0 +1,144 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::client::Token;
+use crate::error::Error;
+
+pub trait TokenStore: Send + Sync {
+    fn load(&self) -> impl Future<Output = Result<Option<Token>, Error>> + Send;
+
+    fn store(&self, token: &Token) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+/// Saves a token as JSON in a file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    #[tracing::instrument(skip_all, fields(path = %self.path.display()))]
+    async fn load(&self) -> Result<Option<Token>, Error> {
+        let buf = match tokio::fs::read(&self.path).await {
+            Ok(buf) => buf,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::debug!("no token stored");
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let token: Token = serde_json::from_slice(&buf)?;
+        Ok(Some(token))
+    }
+
+    /// Writes to a temporary file next to [`Self::path`] and renames it over the target,
+    /// so that a crash never leaves a partial token behind.
+    /// On unix the file is created readable by the owner only.
+    #[tracing::instrument(skip_all, fields(path = %self.path.display()))]
+    async fn store(&self, token: &Token) -> Result<(), Error> {
+        let buf = serde_json::to_vec_pretty(token)?;
+        let temp = self.temp_path();
+        if let Err(e) = write_private(&temp, &buf).await {
+            let _ = tokio::fs::remove_file(&temp).await;
+            return Err(e.into());
+        }
+        tokio::fs::rename(&temp, &self.path).await?;
+        tracing::debug!("stored token");
+        Ok(())
+    }
+}
+
+impl FileTokenStore {
+    /// Unique per call, so that concurrent stores to the same path never share a file.
+    fn temp_path(&self) -> PathBuf {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let temp = format!(".{name}.{}.{seq}.tmp", std::process::id());
+        self.path.with_file_name(temp)
+    }
+}
+
+async fn write_private(path: &Path, buf: &[u8]) -> std::io::Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    // a leftover from a crashed run may carry other permissions
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path).await?;
+    file.write_all(buf).await?;
+    file.sync_all().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::Calendar;
+    use crate::test_util::token_response;
+
+    #[tokio::test]
+    async fn test_file_token_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileTokenStore::new(dir.path().join("token.json"));
+        assert_eq!(store.load().await.unwrap(), None);
+
+        let mut payload = token_response(&Calendar);
+        payload["refresh_token"] = "refresh".into();
+        let token: Token = serde_json::from_value(payload).unwrap();
+        store.store(&token).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(token.clone()));
+        store.store(&token).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(token));
+
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1, "temporary file left behind");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = std::fs::metadata(store.path())
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_token_store_concurrent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token.json");
+        let stores = (0..8).map(|i| {
+            let store = FileTokenStore::new(&path);
+            let token = Token::builder().access_token(format!("access-{i}")).build();
+            tokio::spawn(async move { store.store(&token).await })
+        });
+        for handle in stores.collect::<Vec<_>>() {
+            handle.await.unwrap().unwrap();
+        }
+
+        let token = FileTokenStore::new(&path).load().await.unwrap().unwrap();
+        assert!(token.access_token().starts_with("access-"));
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1, "temporary file left behind");
+    }
+}
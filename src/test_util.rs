@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use futures::future::BoxFuture;
+
+use crate::backend::{HttpClient, HttpRequest, HttpResponse};
+use crate::client::{
+    unix_now, AuthorizedClient, Token, TokenBuilder, UnauthorizedClient, UnauthorizedClientBuilder,
+};
+use crate::error::BoxError;
+use crate::scope::{Calendar, Scope, SingleScope};
+use crate::secret::WebClientSecret;
+
+/// Serves `router` on an ephemeral local port for the rest of the test.
+pub(crate) async fn serve(router: axum::Router) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    addr
+}
+
+pub(crate) fn secret() -> WebClientSecret {
+    WebClientSecret {
+        client_id: "client_id".to_string(),
+        project_id: "project_id".to_string(),
+        auth_uri: "https://accounts.google.com/o/oauth2/auth".to_string(),
+        token_uri: "https://oauth2.googleapis.com/token".to_string(),
+        auth_provider_x509_cert_url: "https://www.googleapis.com/oauth2/v1/certs".to_string(),
+        client_secret: "client_secret".into(),
+        redirect_uris: vec!["http://localhost:8080/oauth2/callback".to_string()],
+    }
+}
+
+/// A fresh `access` token granting `scope`, to adjust before building.
+pub(crate) fn token(scope: &dyn SingleScope) -> TokenBuilder {
+    Token::builder()
+        .access_token("access")
+        .expires_in(3599)
+        .obtained_at(unix_now())
+        .scope(vec![scope.as_dyn()].into())
+}
+
+/// A client authorized with [`token`] and [`secret`].
+pub(crate) fn authorized(scope: &dyn SingleScope) -> AuthorizedClient {
+    AuthorizedClient::new(secret(), token(scope).build())
+}
+
+/// A `stale` [`token`] client holding the refresh token `refresh/1`,
+/// sending through `http`.
+pub(crate) fn refreshable(http: MockHttpClient) -> AuthorizedClient {
+    let token = token(&Calendar)
+        .access_token("stale")
+        .refresh_token("refresh/1")
+        .build();
+    AuthorizedClient::new(secret(), token).with_http_client(http)
+}
+
+/// A builder for the `calendar` scope with [`secret`] and its registered redirect URI,
+/// to adjust before building.
+pub(crate) fn unauthorized_builder() -> UnauthorizedClientBuilder<impl Scope + Clone> {
+    UnauthorizedClient::builder()
+        .redirect_uri("http://localhost:8080/oauth2/callback")
+        .add_scope(Calendar)
+        .secret(&secret())
+}
+
+/// [`unauthorized_builder`], built and sending through `http`.
+pub(crate) fn unauthorized(http: MockHttpClient) -> UnauthorizedClient {
+    unauthorized_builder()
+        .build()
+        .unwrap()
+        .with_http_client(http)
+}
+
+/// The token endpoint's reply granting an `access` token for `scope`.
+pub(crate) fn token_response(scope: &dyn SingleScope) -> serde_json::Value {
+    serde_json::json!({
+        "access_token": "access",
+        "expires_in": 3599,
+        "scope": scope.as_str(),
+        "token_type": "Bearer"
+    })
+}
+
+/// In-memory [`HttpClient`] replying with queued responses and recording requests.
+#[derive(Clone, Default)]
+pub(crate) struct MockHttpClient {
+    responses: Arc<Mutex<VecDeque<HttpResponse>>>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl MockHttpClient {
+    pub(crate) fn push_json(&self, status: http::StatusCode, body: serde_json::Value) {
+        let response = http::Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&body).unwrap())
+            .unwrap();
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    pub(crate) fn take_requests(&self) -> Vec<HttpRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+impl HttpClient for MockHttpClient {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, BoxError>> {
+        self.requests.lock().unwrap().push(request);
+        let response = self.responses.lock().unwrap().pop_front();
+        Box::pin(async move { response.ok_or_else(|| "no response queued".into()) })
+    }
+}